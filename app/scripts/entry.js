@@ -10,51 +10,109 @@ import { Painter } from "./painter.js";
 
 class Entry {
     exports;
-    log = "";
 
     painter = new Painter();
 
     imports = {
         env: {
-            js_log: function (log) {
-                let c = String.fromCharCode(log);
-                if (c == "\n") {
-                    console.log(this.log);
-                    this.log = "";
-                }
-                else this.log += c;
+            js_log: function (ptr, len) {
+                console.log(this.read_string(ptr, len));
             }.bind(this),
             js_random: function (max) {
                 return Math.floor(Math.random() * max);
             },
-            js_draw_rect: function (x, y, w, h, r, g, b, a) {
-                this.painter.draw_rect(x, y, w, h, this.painter.color(r, g, b, a));
-            }.bind(this),
-            js_draw_image: function (index, sx, sy, sw, sh, dx, dy, dw, dh) {
-                this.painter.draw_image(index, sx, sy, sw, sh, dx, dy, dw, dh);
-            }.bind(this),
+            js_now: function () {
+                return performance.now();
+            },
+        }
+    }
+
+    // Decode UTF-8 text in linear memory owned by Rust.
+    read_string(ptr, len) {
+        return new TextDecoder().decode(new Uint8Array(this.exports.memory.buffer, ptr, len));
+    }
+
+    // Copy UTF-8 text into linear memory from alloc.
+    // The returned [ptr, len] must be passed to Rust or freed by dealloc.
+    write_string(text) {
+        let bytes = new TextEncoder().encode(text);
+        let ptr = this.exports.alloc(bytes.length);
+        new Uint8Array(this.exports.memory.buffer, ptr, bytes.length).set(bytes);
+        return [ptr, bytes.length];
+    }
+
+    // Change filter of log at runtime (e.g. "warn,wani_map=trace").
+    set_log_filter(filter) {
+        let [ptr, len] = this.write_string(filter);
+        this.exports.set_log_filter(ptr, len);
+    }
+
+    // Fit canvas to the window.
+    resize() {
+        this.painter.resize(window.innerWidth, window.innerHeight);
+        if (this.exports) {
+            this.exports.resize(window.innerWidth, window.innerHeight);
         }
     }
 
     constructor() {
+        this.resize();
+        window.addEventListener("resize", this.resize.bind(this));
+
         fetch("wani_rogue.wasm")
             .then((response) => response.arrayBuffer())
             .then((bytes) => WebAssembly.instantiate(bytes, this.imports))
             .then((results) => {
                 this.exports = results.instance.exports;
-                this.exports.init(this.painter.buf_canvas.width, this.painter.buf_canvas.height);
-
-                setInterval(() => {
-                    this.exports.update();
-                    this.painter.clear_rect();
-                    this.exports.draw();
-                    this.painter.flip();
-                }, 20); // 50FPS
+                // Same seed reproduces same dungeon (e.g. index.html?seed=1234)
+                let seed = parseInt(new URLSearchParams(location.search).get("seed")) || 0;
+                this.exports.init(this.painter.buf_canvas.width, this.painter.buf_canvas.height, seed);
+
+                // Filter of log (e.g. index.html?log=wani_map=trace)
+                let filter = new URLSearchParams(location.search).get("log");
+                if (filter) {
+                    this.set_log_filter(filter);
+                }
+
+                // Magnification of drawing (e.g. index.html?zoom=2)
+                let zoom = parseFloat(new URLSearchParams(location.search).get("zoom"));
+                if (zoom) {
+                    this.exports.set_zoom(zoom);
+                }
+
+                let last = performance.now();
+                let frame = (now) => {
+                    try {
+                        // Logic runs in fixed steps inside, whatever the frame rate is
+                        this.exports.update(now - last);
+                        last = now;
+                        this.painter.clear_rect();
+                        this.exports.draw();
+                        this.painter.replay(
+                            this.exports.memory.buffer,
+                            this.exports.draw_buffer_ptr(),
+                            this.exports.draw_buffer_len());
+                        this.painter.flip();
+                    } catch (e) {
+                        // Panic message is already logged by the panic hook
+                        if (!this.exports.is_halted()) {
+                            throw e;
+                        }
+                    }
+                    if (this.exports.is_halted()) {
+                        console.error("wani_rogue is halted.");
+                    } else {
+                        requestAnimationFrame(frame);
+                    }
+                };
+                requestAnimationFrame(frame);
             });
     }
 }
 
 window.onload = () => {
     const entry = new Entry();
+    // Accessible from console of web browser
+    window.wani = entry;
     new Input(entry);
 };
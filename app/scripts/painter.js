@@ -26,6 +26,13 @@ export class Painter {
         this.resource = new Resource();
     }
 
+    resize(width, height) {
+        this.main_canvas.width = width;
+        this.main_canvas.height = height;
+        this.buf_canvas.width = width;
+        this.buf_canvas.height = height;
+    }
+
     flip() {
         let img = this.buf_context.getImageData(0, 0, this.buf_canvas.width, this.buf_canvas.height);
         this.main_context.putImageData(img, 0, 0);
@@ -44,6 +51,36 @@ export class Painter {
         return "rgba(" + r + "," + g + "," + b + "," + a / 0xff + ")";
     }
 
+    // Draw commands encoded by draw_buffer.rs
+    replay(buffer, ptr, len) {
+        const RECT = 0;
+        const IMAGE = 1;
+
+        let cmd = new Int32Array(buffer, ptr, len);
+        let i = 0;
+        while (i < len) {
+            switch (cmd[i]) {
+                case RECT: {
+                    let rgba = cmd[i + 5];
+                    let color = this.color(
+                        (rgba >>> 24) & 0xff, (rgba >>> 16) & 0xff, (rgba >>> 8) & 0xff, rgba & 0xff);
+                    this.draw_rect(cmd[i + 1], cmd[i + 2], cmd[i + 3], cmd[i + 4], color);
+                    i += 6;
+                    break;
+                }
+                case IMAGE:
+                    this.draw_image(
+                        cmd[i + 1],
+                        cmd[i + 2], cmd[i + 3], cmd[i + 4], cmd[i + 5],
+                        cmd[i + 6], cmd[i + 7], cmd[i + 8], cmd[i + 9]);
+                    i += 10;
+                    break;
+                default:
+                    return;
+            }
+        }
+    }
+
     draw_image(index, sx, sy, sw, sh, dx, dy, dw, dh) {
         let img = this.resource.get_image(index);
         if (img) {
@@ -6,7 +6,8 @@
 // http://opensource.org/licenses/mit-license.php
 
 export class Resource {
-    res_name = ["./res/mapchip.png"];
+    // Index is the image index of draw calls. Keep in sync with src/wani_core/image.rs.
+    res_name = ["./res/mapchip.png", "./res/hero.png"];
 
     image;
     load_flag;
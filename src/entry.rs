@@ -5,93 +5,194 @@
 //! This source code is released under the MIT License
 //! http://opensource.org/licenses/mit-license.php
 
-use std::ops::Range;
-use std::sync::Mutex;
+use std::cell::RefCell;
+use std::ptr;
+
+use crate::{debug, info, trace, warn};
 
 use crate::wani_core::color::Color;
 use crate::wani_core::rect::Rect;
 
-use crate::game_manager::GAME_MANAGER;
-use crate::wani_core::input_manager::INPUT_MANAGER;
-
-extern "C" {
-    fn js_log(log: u32);
-    fn js_random(max: isize) -> isize;
-    fn js_draw_rect(x: isize, y: isize, w: usize, h: usize, r: u8, g: u8, b: u8, a: u8);
-    fn js_draw_image(
-        index: usize,
-        sx: isize,
-        sy: isize,
-        sw: usize,
-        sh: usize,
-        dx: isize,
-        dy: isize,
-        dw: usize,
-        dh: usize,
-    );
-}
-
-/// Log message to console in web browser.
+use crate::wani_core::logger::{Level, LOGGER};
+use crate::wani_core::panic_hook;
+use crate::wani_core::tile_metrics::TileMetrics;
+use crate::wani_platform::memory;
+use crate::wani_platform::with_platform;
+use crate::wani_state::state_stack::{Context, StateStack};
+use crate::wani_state::title::Title;
+
+/// Log message to console of the platform.
 pub fn log(log: &str) {
-    unsafe {
-        for c in log.chars() {
-            js_log(c as u32)
-        }
-        js_log('\n' as u32);
-    }
+    with_platform(|p| p.log(log))
 }
 
-/// Generate random numbers from range.
-pub fn random(range: Range<isize>) -> isize {
-    unsafe { js_random(range.end - range.start) + range.start }
+pub fn draw_rect(rect: Rect, color: Color) {
+    with_platform(|p| p.draw_rect(rect, color))
 }
-/// Generate random numbers from range<usize>.
-pub fn urandom(range: Range<usize>) -> usize {
-    unsafe { js_random((range.end - range.start) as isize) as usize + range.start }
+
+pub fn draw_image(index: usize, src: Rect, dist: Rect) {
+    with_platform(|p| p.draw_image(index, src, dist))
 }
 
-pub fn draw_rect(rect: Rect, color: Color) {
-    unsafe {
-        js_draw_rect(
-            rect.x, rect.y, rect.w, rect.h, color.r, color.g, color.b, color.a,
-        )
-    }
+/// Return elapsed time in milliseconds.
+pub fn now() -> f64 {
+    with_platform(|p| p.now())
 }
 
-pub fn draw_image(index: usize, src: Rect, dist: Rect) {
-    unsafe {
-        js_draw_image(
-            index, src.x, src.y, src.w, src.h, dist.x, dist.y, dist.w, dist.h,
-        )
-    }
+thread_local! {
+    /// States driven by exports. Created by `init`.
+    static STATES: RefCell<Option<StateStack>> = const { RefCell::new(None) };
 }
 
-lazy_static! {
-    pub static ref SCREEN_SIZE: Mutex<Rect> = Mutex::new(Rect::new(0, 0, 0, 0));
+/// Run `f` with the states of exports. Return `None` before `init` and after halted.
+///
+/// On wasm a panic leaves the states borrowed, so they are never touched again.
+fn with_states<F, R>(f: F) -> Option<R>
+where
+    F: FnOnce(&mut StateStack) -> R,
+{
+    if panic_hook::is_halted() {
+        return None;
+    }
+    STATES.with(|s| s.borrow_mut().as_mut().map(f))
 }
 
 /// Call point from Javascript.
+///
+/// Start from the title screen.
+/// The first run is reproduced by passing same `seed`.
+/// If `seed` is 0, it is decided by the platform.
+#[no_mangle]
+pub fn init(width: usize, height: usize, seed: u32) {
+    panic_hook::install();
+
+    let ctx = Context::new(Rect::new(0, 0, width, height), seed);
+    let states = StateStack::new(ctx, Box::new(Title));
+    STATES.with(|s| *s.borrow_mut() = Some(states));
+}
+
+/// Return seed of current run, or 0 out of a run.
 #[no_mangle]
-pub fn init(width: usize, height: usize) {
-    *SCREEN_SIZE.lock().unwrap() = Rect::new(0, 0, width, height);
+pub fn get_seed() -> u32 {
+    with_states(|s| s.context().game.as_ref().map(|g| g.seed()))
+        .flatten()
+        .unwrap_or(0)
+}
+
+/// Allocate memory for JavaScript to pass bytes.
+#[no_mangle]
+pub fn alloc(size: usize) -> *mut u8 {
+    memory::alloc(size)
+}
+
+/// Free memory from `alloc` not passed to Rust.
+///
+/// # Safety
+/// `ptr` and `size` must be a pair returned by and passed to `alloc`.
+#[no_mangle]
+pub unsafe fn dealloc(ptr: *mut u8, size: usize) {
+    memory::dealloc(ptr, size)
+}
+
+/// Set threshold of log. (0: Trace, 1: Debug, 2: Info, 3: Warn, 4: Error, 5: Off)
+#[no_mangle]
+pub fn set_log_level(level: usize) {
+    LOGGER.lock().unwrap().set_level(Level::from_usize(level));
+}
+
+/// Set filter of log like `warn,wani_map=trace`.
+///
+/// # Safety
+/// The text must be written to memory from `alloc`.
+#[no_mangle]
+pub unsafe fn set_log_filter(ptr: *mut u8, len: usize) {
+    let filter = memory::take_string(ptr, len);
+    LOGGER.lock().unwrap().set_filter(&filter);
+    info!("log filter: {}", filter);
 }
 
 #[no_mangle]
 pub fn key_down(key_code: usize) {
-    INPUT_MANAGER.lock().unwrap().key_down(key_code);
+    trace!("key down: {}", key_code);
+    with_states(|s| s.key_down(key_code));
 }
 
 #[no_mangle]
 pub fn key_up(key_code: usize) {
-    INPUT_MANAGER.lock().unwrap().key_up(key_code);
+    trace!("key up: {}", key_code);
+    with_states(|s| s.key_up(key_code));
+}
+
+/// Call point from Javascript when the canvas is resized.
+#[no_mangle]
+pub fn resize(width: usize, height: usize) {
+    debug!("resize: {}x{}", width, height);
+    with_states(|s| s.context_mut().resize(Rect::new(0, 0, width, height)));
+}
+
+/// Change size of tile in tileset image and on screen.
+///
+/// Sizes of 0 are ignored and the current metrics are kept.
+#[no_mangle]
+pub fn set_tile_metrics(source_size: usize, screen_size: usize, atlas_columns: usize) {
+    match TileMetrics::new(source_size, screen_size, atlas_columns) {
+        Some(metrics) => {
+            with_states(|s| s.context_mut().set_tile_metrics(metrics));
+        }
+        None => warn!(
+            "ignore tile metrics {}, {}, {}",
+            source_size, screen_size, atlas_columns
+        ),
+    }
+}
+
+/// Change magnification of drawing. 2.0 draws tiles twice as large.
+#[no_mangle]
+pub fn set_zoom(zoom: f64) {
+    with_states(|s| s.context_mut().set_zoom(zoom));
 }
 
+/// Return whether the game is halted by panic.
+///
+/// Once halted, exports touching the game do nothing.
 #[no_mangle]
-pub fn update() {
-    GAME_MANAGER.lock().unwrap().update();
+pub fn is_halted() -> bool {
+    panic_hook::is_halted()
 }
 
+/// Advance the game by elapsed milliseconds from last call.
+#[no_mangle]
+pub fn update(elapsed: f64) {
+    with_states(|s| s.update(elapsed));
+}
+
+/// Record draw commands of a frame.
+///
+/// The commands are read through `draw_buffer_ptr` and `draw_buffer_len`.
 #[no_mangle]
 pub fn draw() {
-    GAME_MANAGER.lock().unwrap().draw();
+    // Platform may be left borrowed by the panic
+    if panic_hook::is_halted() {
+        return;
+    }
+    with_platform(|p| p.begin_frame());
+    with_states(|s| s.draw());
+}
+
+/// Return address of draw commands in linear memory.
+#[no_mangle]
+pub fn draw_buffer_ptr() -> *const i32 {
+    if panic_hook::is_halted() {
+        return ptr::null();
+    }
+    with_platform(|p| p.draw_buffer().as_ptr())
+}
+
+/// Return number of `i32` words of draw commands.
+#[no_mangle]
+pub fn draw_buffer_len() -> usize {
+    if panic_hook::is_halted() {
+        return 0;
+    }
+    with_platform(|p| p.draw_buffer().len())
 }
@@ -0,0 +1,499 @@
+//! Context of a game for wani_rogue
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::wani_character::hero;
+use crate::wani_core::camera::Camera;
+use crate::wani_core::color::Color;
+use crate::wani_core::handle::{Handle, ObjectId};
+use crate::wani_core::input_manager::InputManager;
+use crate::wani_core::random::{Random, RandomStream};
+use crate::wani_core::rect::Rect;
+use crate::wani_core::scheduler::TurnScheduler;
+use crate::wani_core::tile_metrics::TileMetrics;
+use crate::wani_core::vector2::Vec2;
+use crate::wani_ecs::component::{Components, Player};
+use crate::wani_ecs::event::{EventBus, FloorChanged};
+use crate::wani_ecs::system;
+use crate::wani_ecs::world::World;
+use crate::wani_map::random_map;
+use crate::wani_map::random_map::RandomMap;
+
+/// Interval of update step in milliseconds.
+pub const TIME_STEP: f64 = 20.0;
+/// Maximum update steps at once.
+const MAX_STEPS: f64 = 5.0;
+/// Time of fade on entering a floor in milliseconds.
+const FLOOR_FADE: f64 = 400.0;
+/// Maximum actions in an update step.
+const MAX_ACTIONS: usize = 1000;
+
+/// Everything of a running game.
+///
+/// Games share nothing, so several ones can run side by side in a process.
+pub struct Game {
+    world: World,
+    input: InputManager,
+    tile_metrics: TileMetrics,
+
+    main_camera: Camera,
+    scheduler: TurnScheduler,
+
+    accumulator: f64,
+
+    seed: u32,
+    /// Random number stream for floor generation.
+    map_random: Random,
+    /// Random number stream for gameplay rolls.
+    combat_random: Random,
+    /// Random number stream for effects not affecting gameplay.
+    cosmetic_random: Random,
+
+    pub hero: Handle<Player>,
+    pub map: Handle<RandomMap>,
+}
+
+impl Game {
+    /// Start a game on the screen. Same `seed` generates same floor.
+    pub fn new(seed: u32, screen: Rect) -> Self {
+        let mut world = World::new();
+
+        let mut map_random = Random::derive(seed, RandomStream::Map);
+        let (map, coord) = Game::spawn_floor(&mut world, &mut map_random);
+        let hero = world.spawn(hero::components(coord));
+        let hero = world.handle(hero).unwrap();
+
+        let mut scheduler = TurnScheduler::new();
+        for (entity, actor) in world.actors.iter() {
+            scheduler.add(entity, actor.speed);
+        }
+
+        let mut game = Game {
+            world,
+            input: InputManager::new(),
+            tile_metrics: TileMetrics::default(),
+
+            main_camera: Camera::new(),
+            scheduler,
+
+            accumulator: 0.0,
+
+            seed,
+            map_random,
+            combat_random: Random::derive(seed, RandomStream::Combat),
+            cosmetic_random: Random::derive(seed, RandomStream::Cosmetic),
+
+            hero,
+            map,
+        };
+        game.resize(screen);
+        game.main_camera
+            .fade_from(Color::new(0x00, 0x00, 0x00, 0xff), FLOOR_FADE);
+        game
+    }
+
+    /// Spawn a floor generated from the map stream. Return it and a tile to put the hero on.
+    fn spawn_floor(world: &mut World, map_random: &mut Random) -> (Handle<RandomMap>, Vec2) {
+        let map = RandomMap::new(80, 50, map_random);
+        let coord = map.respawnable_coord(map_random);
+
+        let map = world.spawn(random_map::components(map));
+        let map = world.handle(map).unwrap();
+        world.events.floor_changed.publish(FloorChanged { map });
+        (map, coord)
+    }
+
+    /// Replace the floor with next one, and put the hero on it.
+    pub fn next_floor(&mut self) {
+        self.world.despawn(self.map.id());
+        let (map, coord) = Game::spawn_floor(&mut self.world, &mut self.map_random);
+        self.map = map;
+        system::teleport(&mut self.world, self.hero.id(), coord);
+
+        self.snap_camera();
+        self.main_camera
+            .fade_from(Color::new(0x00, 0x00, 0x00, 0xff), FLOOR_FADE);
+    }
+
+    /// Advance game logic by elapsed milliseconds in fixed time steps.
+    pub fn update(&mut self, elapsed: f64) {
+        // Drop the time which can not catch up with
+        self.accumulator = (self.accumulator + elapsed.max(0.0)).min(TIME_STEP * MAX_STEPS);
+
+        while TIME_STEP <= self.accumulator {
+            // Animation runs every step and actions run when their turn comes
+            system::animate(&mut self.world, TIME_STEP);
+            self.process_turns();
+            system::update_poses(&mut self.world);
+            self.world.apply_commands();
+            // Handlers see the world after all changes of the step
+            self.world.events.deliver();
+            self.sync_scheduler();
+            self.update_camera();
+            self.main_camera
+                .update_effects(TIME_STEP, &mut self.cosmetic_random);
+            self.accumulator -= TIME_STEP;
+        }
+    }
+
+    /// Let actors act until someone waits.
+    fn process_turns(&mut self) {
+        // Guard against actors acting without cost forever
+        for _ in 0..MAX_ACTIONS {
+            let id = match self.scheduler.next_actor() {
+                Some(id) => id,
+                None => return,
+            };
+            // Killed actor does not act before it is removed
+            if !self.world.is_alive(id) || self.world.is_despawning(id) {
+                self.scheduler.remove(id);
+                continue;
+            }
+            let cost = if self.world.players.contains(id) {
+                hero::act(&mut self.world, self.map, id, &self.input)
+            } else if self.world.ais.contains(id) {
+                system::ai_act(&mut self.world, id, &mut self.combat_random)
+            } else {
+                self.scheduler.remove(id);
+                continue;
+            };
+            match cost {
+                Some(cost) => self.scheduler.spend(id, cost),
+                None => return,
+            }
+        }
+    }
+
+    /// Add spawned actors to the scheduler and remove despawned ones.
+    fn sync_scheduler(&mut self) {
+        for e in self.world.events.spawned.read() {
+            // Actors spawned in `new` are scheduled already
+            if self.scheduler.contains(e.entity) {
+                continue;
+            }
+            if let Some(actor) = self.world.actors.get(e.entity) {
+                self.scheduler.add(e.entity, actor.speed);
+            }
+        }
+        for e in self.world.events.despawned.read() {
+            self.scheduler.remove(e.entity);
+        }
+    }
+
+    /// Queue entity to be added after current update step, and return it.
+    ///
+    /// Actor is added to the scheduler then.
+    pub fn spawn(&mut self, components: Components) -> ObjectId {
+        self.world.spawn_later(components)
+    }
+
+    /// Queue entity to be removed after current update step.
+    pub fn despawn(&mut self, entity: ObjectId) {
+        self.world.despawn_later(entity);
+    }
+
+    pub fn world(&self) -> &World {
+        &self.world
+    }
+
+    pub fn world_mut(&mut self) -> &mut World {
+        &mut self.world
+    }
+
+    /// Return channels to publish and subscribe events.
+    pub fn events_mut(&mut self) -> &mut EventBus {
+        &mut self.world.events
+    }
+
+    /// Return number of elapsed turns.
+    pub fn turn(&self) -> u64 {
+        self.scheduler.turn()
+    }
+
+    /// Return progress from last update step to next one in `0.0..1.0`.
+    pub fn alpha(&self) -> f64 {
+        self.accumulator / TIME_STEP
+    }
+
+    /// Follow the change of screen size.
+    pub fn resize(&mut self, screen: Rect) {
+        self.main_camera.set_screen(screen);
+        self.snap_camera();
+    }
+
+    pub fn screen(&self) -> Rect {
+        self.main_camera.screen()
+    }
+
+    pub fn input(&self) -> &InputManager {
+        &self.input
+    }
+
+    pub fn input_mut(&mut self) -> &mut InputManager {
+        &mut self.input
+    }
+
+    pub fn tile_metrics(&self) -> TileMetrics {
+        self.tile_metrics
+    }
+
+    /// Change size of tile in tileset image and on screen.
+    pub fn set_tile_metrics(&mut self, tile_metrics: TileMetrics) {
+        self.tile_metrics = tile_metrics;
+        self.snap_camera();
+    }
+
+    /// Change magnification of drawing.
+    pub fn set_zoom(&mut self, zoom: f64) {
+        self.main_camera.set_zoom(zoom);
+        self.snap_camera();
+    }
+
+    pub fn camera(&self) -> &Camera {
+        &self.main_camera
+    }
+
+    pub fn camera_mut(&mut self) -> &mut Camera {
+        &mut self.main_camera
+    }
+
+    /// Return world position of the center of hero at current update step.
+    fn camera_target(&self) -> Option<Vec2> {
+        let half_tile = self.tile_metrics.screen_size as isize / 2;
+        let pos = system::draw_position(&self.world, self.hero.id(), &self.tile_metrics, 1.0)?;
+        Some(pos + Vec2::new(half_tile, half_tile))
+    }
+
+    /// Return world rect of the map.
+    fn map_bounds(&self) -> Option<Rect> {
+        let tile = self.tile_metrics.screen_size;
+        let map = self.world.get(self.map)?;
+        Some(Rect::new(
+            0,
+            0,
+            map.size.width * tile,
+            map.size.height * tile,
+        ))
+    }
+
+    /// Look at the hero without following.
+    fn snap_camera(&mut self) {
+        let bounds = self.map_bounds();
+        self.main_camera.set_bounds(bounds);
+        if let Some(target) = self.camera_target() {
+            self.main_camera.look_at(target);
+        }
+    }
+
+    /// Follow the hero inside of the map.
+    fn update_camera(&mut self) {
+        let bounds = self.map_bounds();
+        self.main_camera.set_bounds(bounds);
+        if let Some(target) = self.camera_target() {
+            self.main_camera.follow_target(target);
+        }
+    }
+
+    pub fn draw(&self) {
+        let alpha = self.alpha();
+        let camera = self.main_camera.interpolated(alpha);
+        system::render(&self.world, &camera, &self.tile_metrics, alpha);
+        camera.draw_effects();
+    }
+
+    /// Return seed of this run.
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wani_character::sprite_sheet::Pose;
+    use crate::wani_core::input_manager::InputKey;
+    use crate::wani_core::scheduler::NORMAL_SPEED;
+    use crate::wani_ecs::component::{Actor, Ai, Position, MOVE_DURATION};
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    fn screen() -> Rect {
+        Rect::new(0, 0, 640, 480)
+    }
+
+    #[test]
+    fn keep_hero_in_view_after_resize() {
+        let mut game = Game::new(1, screen());
+        game.resize(Rect::new(0, 0, 480, 640));
+
+        let screen = game.camera().screen();
+        assert!(screen.w == 480 && screen.h == 640);
+        let hero = game.camera().world_to_screen(game.camera_target().unwrap());
+        assert!(screen.contains(hero));
+
+        // Map edge is not scrolled into view
+        let world = game.camera().visible_world_rect();
+        assert!(0 <= world.left() && 0 <= world.top());
+    }
+
+    #[test]
+    fn fixed_time_step() {
+        let mut game = Game::new(1, screen());
+        game.update(50.0);
+        assert!((game.alpha() - 0.5).abs() < 1e-9);
+        game.update(15.0);
+        assert!((game.alpha() - 0.25).abs() < 1e-9);
+
+        // Long pause does not run too many steps
+        game.update(10000.0);
+        assert!(game.alpha() < 1.0);
+    }
+
+    #[test]
+    fn wait_for_player() {
+        let mut game = Game::new(1, screen());
+        game.update(TIME_STEP * 3.0);
+        assert_eq!(game.turn(), 1);
+    }
+
+    #[test]
+    fn keep_walking_between_tiles() {
+        let mut game = Game::new(1, screen());
+        let hero = game.hero.id();
+        let coord = |g: &Game| g.world().positions.get(hero).unwrap().coord;
+        let start = coord(&game);
+
+        // Direction with two free tiles ahead
+        let keys = [
+            (38, Vec2::new(0, -1)),
+            (40, Vec2::new(0, 1)),
+            (37, Vec2::new(-1, 0)),
+            (39, Vec2::new(1, 0)),
+        ];
+        let free = |d: Vec2| {
+            system::is_walkable(game.world(), game.map, start + d)
+                && system::is_walkable(game.world(), game.map, start + d * 2)
+        };
+        let &(key, dir) = keys.iter().find(|(_, d)| free(*d)).unwrap();
+
+        // No idle pose at the end of first tile
+        game.input_mut().key_down(key);
+        let steps = (MOVE_DURATION * 2.0 / TIME_STEP) as usize;
+        for _ in 0..steps {
+            game.update(TIME_STEP);
+            let animator = game.world().animators.get(hero).unwrap();
+            assert_eq!(animator.pose(), Pose::Walk);
+        }
+        assert!(coord(&game) == start + dir * 2);
+    }
+
+    #[test]
+    fn spawn_between_steps() {
+        let mut game = Game::new(1, screen());
+        let monster = || Components {
+            position: Some(Position::new(Vec2::new(1, 1))),
+            ai: Some(Ai::Wait),
+            actor: Some(Actor::new(NORMAL_SPEED)),
+            ..Default::default()
+        };
+        let id = game.spawn(monster());
+        assert!(!game.world().is_alive(id));
+
+        game.update(TIME_STEP);
+        assert!(game.world().is_alive(id));
+        assert!(game.scheduler.contains(id));
+
+        game.despawn(id);
+        assert!(game.world().is_alive(id));
+        game.update(TIME_STEP);
+        assert!(!game.world().is_alive(id));
+        assert!(!game.scheduler.contains(id));
+
+        // Spawned and despawned in same step never appears
+        let id = game.spawn(monster());
+        game.despawn(id);
+        game.update(TIME_STEP);
+        assert!(!game.world().is_alive(id));
+
+        // Immediate spawn and despawn are followed likewise
+        let id = game.world_mut().spawn(monster());
+        game.update(TIME_STEP);
+        assert!(game.scheduler.contains(id));
+        game.world_mut().despawn(id);
+        game.update(TIME_STEP);
+        assert!(!game.scheduler.contains(id));
+    }
+
+    #[test]
+    fn deliver_events_in_update() {
+        let mut game = Game::new(1, screen());
+        let floors = Rc::new(RefCell::new(Vec::new()));
+        let f = floors.clone();
+        game.events_mut()
+            .floor_changed
+            .subscribe(move |e| f.borrow_mut().push(e.map));
+
+        // Event of entering first floor waits for the first step
+        assert!(floors.borrow().is_empty());
+        game.update(TIME_STEP);
+        assert_eq!(*floors.borrow(), vec![game.map]);
+        game.update(TIME_STEP);
+        assert_eq!(floors.borrow().len(), 1);
+    }
+
+    #[test]
+    fn independent_games() {
+        let mut a = Game::new(7, screen());
+        let mut b = Game::new(7, screen());
+        let coord = |g: &Game| g.world().positions.get(g.hero.id()).unwrap().coord;
+        let start = coord(&b);
+        assert!(coord(&a) == start);
+
+        // Same seed generates same floor, but input and screen are not shared
+        a.input_mut().key_down(38);
+        a.resize(Rect::new(0, 0, 320, 240));
+        a.update(TIME_STEP);
+        b.update(TIME_STEP);
+        assert!(!b.input().get_key(InputKey::UP));
+        assert!(b.screen().w == 640);
+        assert!(coord(&b) == start);
+    }
+
+    #[test]
+    fn gameplay_rolls_keep_floors() {
+        let mut a = Game::new(7, screen());
+        let mut b = Game::new(7, screen());
+        for _ in 0..10 {
+            b.combat_random.range(0..100);
+        }
+        a.next_floor();
+        b.next_floor();
+
+        // Next floor of the seed does not depend on rolls of other systems
+        let tiles = |g: &Game| {
+            let map = g.world().get(g.map).unwrap();
+            map.map
+                .iter()
+                .map(|row| row.iter().map(|&c| c as u8).collect::<Vec<_>>())
+                .collect::<Vec<_>>()
+        };
+        let coord = |g: &Game| g.world().positions.get(g.hero.id()).unwrap().coord;
+        assert!(tiles(&a) == tiles(&b));
+        assert!(coord(&a) == coord(&b));
+
+        // Old floor is gone
+        assert_eq!(a.world().maps.iter().count(), 1);
+    }
+
+    #[test]
+    fn run_headless() {
+        let mut game = Game::new(1, screen());
+        for _ in 0..10 {
+            game.update(TIME_STEP);
+            game.draw();
+        }
+    }
+}
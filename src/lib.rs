@@ -6,11 +6,13 @@
 //! http://opensource.org/licenses/mit-license.php
 
 pub mod entry;
-pub mod game_manager;
-pub mod game_object_manager;
+pub mod game;
 pub mod wani_character;
 pub mod wani_core;
+pub mod wani_ecs;
 pub mod wani_map;
+pub mod wani_platform;
+pub mod wani_state;
 pub mod wani_trait;
 
 #[macro_use]
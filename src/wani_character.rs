@@ -6,3 +6,4 @@
 //! http://opensource.org/licenses/mit-license.php
 
 pub mod hero;
+pub mod sprite_sheet;
@@ -0,0 +1,161 @@
+//! Table of sprite sheets of characters.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::wani_core::image;
+use crate::wani_core::rect::Rect;
+use crate::wani_core::vector2::Vec2;
+use crate::wani_ecs::component::MOVE_DURATION;
+
+/// Direction a character looks to.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Facing {
+    Down,
+    Left,
+    Right,
+    Up,
+}
+
+impl Facing {
+    /// Return facing of the direction. Diagonal one faces sideways.
+    pub fn from_dir(dir: Vec2) -> Option<Facing> {
+        if dir.x < 0 {
+            Some(Facing::Left)
+        } else if 0 < dir.x {
+            Some(Facing::Right)
+        } else if dir.y < 0 {
+            Some(Facing::Up)
+        } else if 0 < dir.y {
+            Some(Facing::Down)
+        } else {
+            None
+        }
+    }
+
+    fn index(self) -> usize {
+        self as usize
+    }
+}
+
+/// Kind of animation.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Pose {
+    Idle,
+    Walk,
+}
+
+/// Looped sequence of frames.
+pub struct Clip {
+    /// Column and row of frames in the sheet
+    pub frames: &'static [(usize, usize)],
+    /// Time to show a frame in milliseconds
+    pub frame_time: f64,
+}
+
+impl Clip {
+    /// Return the frame shown after elapsed milliseconds.
+    pub fn frame_at(&self, elapsed: f64) -> (usize, usize) {
+        let n = (elapsed / self.frame_time).max(0.0) as usize;
+        self.frames[n % self.frames.len()]
+    }
+}
+
+/// Image of frames in a grid and clips of each pose and facing.
+pub struct SpriteSheet {
+    pub name: &'static str,
+    /// Index of image in `wani_core::image`
+    pub image: usize,
+    pub frame_width: usize,
+    pub frame_height: usize,
+    /// Clips in the order of `Facing`
+    pub idle: [Clip; 4],
+    pub walk: [Clip; 4],
+}
+
+impl SpriteSheet {
+    pub fn clip(&self, pose: Pose, facing: Facing) -> &Clip {
+        match pose {
+            Pose::Idle => &self.idle[facing.index()],
+            Pose::Walk => &self.walk[facing.index()],
+        }
+    }
+
+    /// Return rect of the frame in the image.
+    pub fn source_rect(&self, (column, row): (usize, usize)) -> Rect {
+        Rect::new(
+            (column * self.frame_width) as isize,
+            (row * self.frame_height) as isize,
+            self.frame_width,
+            self.frame_height,
+        )
+    }
+}
+
+/// Return the sheet of the name in `SPRITE_SHEETS`.
+pub fn find(name: &str) -> Option<&'static SpriteSheet> {
+    SPRITE_SHEETS.iter().find(|s| s.name == name)
+}
+
+/// Idle frames are at column 0 and 1, and walk frames at 2 to 5 in the row of facing.
+///
+/// Walk cycle plays once while moving a tile.
+macro_rules! standard_clips {
+    ($row:expr, $idle_time:expr) => {
+        (
+            Clip {
+                frames: &[(0, $row), (1, $row)],
+                frame_time: $idle_time,
+            },
+            Clip {
+                frames: &[(2, $row), (3, $row), (4, $row), (5, $row)],
+                frame_time: MOVE_DURATION / 4.0,
+            },
+        )
+    };
+}
+
+/// Sheet of the standard layout with rows of down, left, right and up.
+macro_rules! standard_sheet {
+    ($name:expr, $image:expr, $idle_time:expr) => {{
+        let down = standard_clips!(0, $idle_time);
+        let left = standard_clips!(1, $idle_time);
+        let right = standard_clips!(2, $idle_time);
+        let up = standard_clips!(3, $idle_time);
+        SpriteSheet {
+            name: $name,
+            image: $image,
+            frame_width: 32,
+            frame_height: 32,
+            idle: [down.0, left.0, right.0, up.0],
+            walk: [down.1, left.1, right.1, up.1],
+        }
+    }};
+}
+
+/// Sheets of all characters. Add an entry with the image to add a character.
+pub static SPRITE_SHEETS: &[SpriteSheet] = &[standard_sheet!("hero", image::HERO, 400.0)];
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn lookup_frames() {
+        let hero = find("hero").unwrap();
+        assert!(find("nobody").is_none());
+
+        let walk = hero.clip(Pose::Walk, Facing::Up);
+        assert_eq!(walk.frame_at(0.0), (2, 3));
+        assert!((walk.frame_time * walk.frames.len() as f64 - MOVE_DURATION).abs() < 1e-9);
+        assert_eq!(walk.frame_at(walk.frame_time * 5.5), (3, 3));
+
+        let src = hero.source_rect(walk.frame_at(0.0));
+        assert_eq!((src.x, src.y, src.w, src.h), (64, 96, 32, 32));
+
+        assert_eq!(Facing::from_dir(Vec2::new(1, -1)), Some(Facing::Right));
+        assert_eq!(Facing::from_dir(Vec2::new(0, 0)), None);
+    }
+}
@@ -7,6 +7,14 @@
 
 pub mod camera;
 pub mod color;
+pub mod handle;
+pub mod image;
 pub mod input_manager;
+pub mod logger;
+pub mod panic_hook;
+pub mod random;
 pub mod rect;
+pub mod scheduler;
+pub mod tile_metrics;
+pub mod tween;
 pub mod vector2;
@@ -5,26 +5,430 @@
 //! This source code is released under the MIT License
 //! http://opensource.org/licenses/mit-license.php
 
-use std::sync::Mutex;
-
+use crate::entry::draw_rect;
+use crate::wani_core::color::Color;
+use crate::wani_core::random::Random;
+use crate::wani_core::rect::Rect;
+use crate::wani_core::tween::{Easing, Tween};
+use crate::wani_core::vector2;
 use crate::wani_core::vector2::Vec2;
 
-lazy_static! {
-    pub static ref DRAW_OFFSET: Mutex<Vec2> = Mutex::new(Vec2::new(0, 0));
+/// Way to follow the target.
+#[derive(Clone, Copy)]
+pub enum Follow {
+    /// Move to the target at once.
+    Instant,
+    /// Approach the target by the rate (0.0..=1.0) per update step.
+    Smooth(f64),
+}
+
+/// Transition covering the screen with a color.
+#[derive(Clone, Copy)]
+struct Fade {
+    color: Color,
+    /// Opacity in `0.0..=1.0`
+    level: Tween<f64>,
 }
 
+/// View of the world drawn on the screen.
+///
+/// World coordinates are pixels at zoom 1.0.
+#[derive(Clone)]
 pub struct Camera {
-    pub offset: Vec2,
+    // World position at the center of screen
+    x: f64,
+    y: f64,
+    prev_x: f64,
+    prev_y: f64,
+
+    zoom: f64,
+    screen: Rect,
+    bounds: Option<Rect>,
+
+    pub follow: Follow,
+    /// Size of area around the center where the target moves without scrolling
+    pub dead_zone: (usize, usize),
+
+    // Effects only change the drawing, not the position
+    /// Amplitude of shake in pixels
+    shake: Option<Tween<f64>>,
+    shake_offset: Vec2,
+    fade: Option<Fade>,
+    fade_level: f64,
+    prev_fade_level: f64,
+}
+
+impl Default for Camera {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl Camera {
     pub fn new() -> Self {
         Camera {
-            offset: Vec2::new(0, 0),
+            x: 0.0,
+            y: 0.0,
+            prev_x: 0.0,
+            prev_y: 0.0,
+
+            zoom: 1.0,
+            screen: Rect::new(0, 0, 0, 0),
+            bounds: None,
+
+            follow: Follow::Instant,
+            dead_zone: (0, 0),
+
+            shake: None,
+            shake_offset: vector2::ZERO,
+            fade: None,
+            fade_level: 0.0,
+            prev_fade_level: 0.0,
+        }
+    }
+
+    pub fn set_screen(&mut self, screen: Rect) {
+        self.screen = screen;
+        self.clamp();
+    }
+
+    pub fn screen(&self) -> Rect {
+        self.screen
+    }
+
+    /// Set magnification of drawing. 2.0 draws the world twice as large.
+    pub fn set_zoom(&mut self, zoom: f64) {
+        self.zoom = zoom.max(0.01);
+        self.clamp();
+    }
+
+    pub fn zoom(&self) -> f64 {
+        self.zoom
+    }
+
+    /// Set world area which the camera does not show outside of.
+    pub fn set_bounds(&mut self, bounds: Option<Rect>) {
+        self.bounds = bounds;
+        self.clamp();
+    }
+
+    /// Return world position at the center of screen.
+    pub fn position(&self) -> Vec2 {
+        Vec2::new(self.x.round() as isize, self.y.round() as isize)
+    }
+
+    /// Move the center to the world position without following.
+    pub fn look_at(&mut self, world: Vec2) {
+        self.x = world.x as f64;
+        self.y = world.y as f64;
+        self.clamp();
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+    }
+
+    /// Follow the target at world position. Called every update step.
+    pub fn follow_target(&mut self, target: Vec2) {
+        self.prev_x = self.x;
+        self.prev_y = self.y;
+
+        fn desired(current: f64, target: f64, dead_zone: usize) -> f64 {
+            let half = dead_zone as f64 / 2.0;
+            if current + half < target {
+                target - half
+            } else if target < current - half {
+                target + half
+            } else {
+                current
+            }
+        }
+        let dx = desired(self.x, target.x as f64, self.dead_zone.0);
+        let dy = desired(self.y, target.y as f64, self.dead_zone.1);
+
+        match self.follow {
+            Follow::Instant => {
+                self.x = dx;
+                self.y = dy;
+            }
+            Follow::Smooth(rate) => {
+                self.x += (dx - self.x) * rate;
+                self.y += (dy - self.y) * rate;
+            }
+        }
+        self.clamp();
+    }
+
+    /// Keep the view inside of bounds. Bounds smaller than the view is centered.
+    fn clamp(&mut self) {
+        let bounds = match self.bounds {
+            Some(b) => b,
+            None => return,
+        };
+
+        fn clamp_axis(pos: f64, half_view: f64, min: isize, max: isize) -> f64 {
+            let (min, max) = (min as f64, max as f64);
+            if max - min <= half_view * 2.0 {
+                (min + max) / 2.0
+            } else {
+                pos.max(min + half_view).min(max - half_view)
+            }
+        }
+        let half_w = self.screen.w as f64 / 2.0 / self.zoom;
+        let half_h = self.screen.h as f64 / 2.0 / self.zoom;
+        self.x = clamp_axis(self.x, half_w, bounds.left(), bounds.right());
+        self.y = clamp_axis(self.y, half_h, bounds.top(), bounds.bottom());
+    }
+
+    /// Shake the screen by `amplitude` pixels decaying in `duration` milliseconds.
+    pub fn shake(&mut self, amplitude: usize, duration: f64) {
+        self.shake = Some(Tween::new(amplitude as f64, 0.0, duration, Easing::Linear));
+    }
+
+    /// Cover the screen with the color gradually in `duration` milliseconds.
+    ///
+    /// The screen stays covered until `fade_from` is called.
+    pub fn fade_to(&mut self, color: Color, duration: f64) {
+        self.fade = Some(Fade {
+            color,
+            level: Tween::new(self.fade_level, 1.0, duration, Easing::Linear),
+        });
+    }
+
+    /// Uncover the screen from the color gradually in `duration` milliseconds.
+    pub fn fade_from(&mut self, color: Color, duration: f64) {
+        self.fade = Some(Fade {
+            color,
+            level: Tween::new(1.0, 0.0, duration, Easing::Linear),
+        });
+        self.fade_level = 1.0;
+        self.prev_fade_level = 1.0;
+    }
+
+    /// Return whether a fade is in progress.
+    pub fn is_fading(&self) -> bool {
+        self.fade.is_some_and(|f| !f.level.is_finished())
+    }
+
+    /// Advance timers of effects by elapsed milliseconds. Called every update step.
+    ///
+    /// `rng` should be a cosmetic stream, so effects do not change the gameplay.
+    pub fn update_effects(&mut self, elapsed: f64, rng: &mut Random) {
+        self.shake_offset = vector2::ZERO;
+        if let Some(shake) = &mut self.shake {
+            if shake.update(elapsed) {
+                self.shake = None;
+            } else {
+                let a = shake.value().round() as isize;
+                self.shake_offset = Vec2::new(rng.range(-a..a + 1), rng.range(-a..a + 1));
+            }
+        }
+
+        self.prev_fade_level = self.fade_level;
+        if let Some(fade) = &mut self.fade {
+            fade.level.update(elapsed);
+            self.fade_level = fade.level.value();
+            // Finished fade out keeps covering the screen
+            if fade.level.is_finished() && fade.level.to() <= 0.0 {
+                self.fade = None;
+            }
         }
     }
 
-    pub fn set(&self) {
-        *DRAW_OFFSET.lock().unwrap() = self.offset;
+    /// Draw effects over the screen. Called after the world is drawn.
+    pub fn draw_effects(&self) {
+        let color = match self.fade {
+            Some(fade) => fade.color,
+            None => return,
+        };
+        let a = (self.fade_level * 255.0).round() as u8;
+        if 0 < a {
+            draw_rect(self.screen, Color::new(color.r, color.g, color.b, a));
+        }
+    }
+
+    /// Return the camera between last and current update step.
+    pub fn interpolated(&self, alpha: f64) -> Camera {
+        let mut camera = self.clone();
+        camera.x = self.prev_x + (self.x - self.prev_x) * alpha;
+        camera.y = self.prev_y + (self.y - self.prev_y) * alpha;
+        camera.fade_level = self.prev_fade_level + (self.fade_level - self.prev_fade_level) * alpha;
+        camera
+    }
+
+    /// Shake is applied, so this is for drawing.
+    pub fn world_to_screen(&self, world: Vec2) -> Vec2 {
+        let center = self.screen.center() + self.shake_offset;
+        Vec2::new(
+            ((world.x as f64 - self.x) * self.zoom).floor() as isize + center.x,
+            ((world.y as f64 - self.y) * self.zoom).floor() as isize + center.y,
+        )
+    }
+
+    /// Shake is not applied, so picking on screen is stable while shaking.
+    pub fn screen_to_world(&self, screen: Vec2) -> Vec2 {
+        let center = self.screen.center();
+        Vec2::new(
+            ((screen.x - center.x) as f64 / self.zoom + self.x).floor() as isize,
+            ((screen.y - center.y) as f64 / self.zoom + self.y).floor() as isize,
+        )
+    }
+
+    /// Return rect on screen of the rect in world.
+    pub fn world_rect_to_screen(&self, rect: Rect) -> Rect {
+        // Convert both corners, so adjacent rects have no gap
+        let lt = self.world_to_screen(Vec2::new(rect.left(), rect.top()));
+        let rb = self.world_to_screen(Vec2::new(rect.right(), rect.bottom()));
+        Rect::new(lt.x, lt.y, (rb.x - lt.x) as usize, (rb.y - lt.y) as usize)
+    }
+
+    /// Return rect in world shown on screen.
+    pub fn visible_world_rect(&self) -> Rect {
+        // Drawing is shifted by shake
+        let lt = Vec2::new(self.screen.left(), self.screen.top()) - self.shake_offset;
+        let rb = Vec2::new(self.screen.right(), self.screen.bottom()) - self.shake_offset;
+        let lt = self.screen_to_world(lt);
+        let rb = self.screen_to_world(rb);
+        Rect::new(
+            lt.x,
+            lt.y,
+            (rb.x - lt.x + 1) as usize,
+            (rb.y - lt.y + 1) as usize,
+        )
+    }
+
+    /// Return world position of the tile coordinate.
+    pub fn tile_to_world(&self, tile: Vec2, tile_size: usize) -> Vec2 {
+        tile * tile_size as isize
+    }
+
+    /// Return tile coordinate containing the world position.
+    pub fn world_to_tile(&self, world: Vec2, tile_size: usize) -> Vec2 {
+        let ts = tile_size.max(1) as isize;
+        Vec2::new(world.x.div_euclid(ts), world.y.div_euclid(ts))
+    }
+
+    /// Return rect on screen of the tile coordinate.
+    pub fn tile_to_screen(&self, tile: Vec2, tile_size: usize) -> Rect {
+        let world = self.tile_to_world(tile, tile_size);
+        self.world_rect_to_screen(Rect::new(world.x, world.y, tile_size, tile_size))
+    }
+
+    /// Return tile coordinate under the position on screen.
+    pub fn screen_to_tile(&self, screen: Vec2, tile_size: usize) -> Vec2 {
+        self.world_to_tile(self.screen_to_world(screen), tile_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    fn camera() -> Camera {
+        let mut camera = Camera::new();
+        camera.set_screen(Rect::new(0, 0, 640, 480));
+        camera
+    }
+
+    #[test]
+    fn convert_with_zoom() {
+        let mut camera = camera();
+        camera.look_at(Vec2::new(1000, 500));
+        assert!(camera.world_to_screen(Vec2::new(1000, 500)) == Vec2::new(320, 240));
+
+        camera.set_zoom(2.0);
+        assert!(camera.world_to_screen(Vec2::new(1010, 490)) == Vec2::new(340, 220));
+        assert!(camera.screen_to_world(Vec2::new(340, 220)) == Vec2::new(1010, 490));
+        assert!(camera.screen_to_tile(Vec2::new(340, 220), 32) == Vec2::new(31, 15));
+
+        let rect = camera.tile_to_screen(Vec2::new(31, 15), 32);
+        assert_eq!((rect.x, rect.y, rect.w, rect.h), (304, 200, 64, 64));
+    }
+
+    #[test]
+    fn clamp_to_bounds() {
+        let mut camera = camera();
+        camera.set_bounds(Some(Rect::new(0, 0, 2000, 1000)));
+
+        camera.look_at(Vec2::new(0, 0));
+        assert!(camera.position() == Vec2::new(320, 240));
+        camera.look_at(Vec2::new(5000, 5000));
+        assert!(camera.position() == Vec2::new(1680, 760));
+
+        // Bounds smaller than the view is centered
+        camera.set_bounds(Some(Rect::new(0, 0, 100, 100)));
+        assert!(camera.position() == Vec2::new(50, 50));
+    }
+
+    #[test]
+    fn follow_with_dead_zone() {
+        let mut camera = camera();
+        camera.dead_zone = (100, 100);
+        camera.look_at(Vec2::new(0, 0));
+
+        camera.follow_target(Vec2::new(40, -40));
+        assert!(camera.position() == Vec2::new(0, 0));
+        camera.follow_target(Vec2::new(80, 0));
+        assert!(camera.position() == Vec2::new(30, 0));
+
+        camera.dead_zone = (0, 0);
+        camera.follow = Follow::Smooth(0.5);
+        camera.follow_target(Vec2::new(130, 0));
+        assert!(camera.position() == Vec2::new(80, 0));
+        assert!(camera.interpolated(0.5).position() == Vec2::new(55, 0));
+    }
+
+    #[test]
+    fn shake_does_not_move_camera() {
+        let mut camera = camera();
+        let mut rng = Random::new(1);
+        camera.look_at(Vec2::new(100, 100));
+        camera.shake(8, 100.0);
+
+        let mut shaken = false;
+        for _ in 0..4 {
+            camera.update_effects(20.0, &mut rng);
+            let shift = camera.world_to_screen(Vec2::new(100, 100)) - Vec2::new(320, 240);
+            assert!(shift.x.abs() <= 8 && shift.y.abs() <= 8);
+            shaken |= shift != vector2::ZERO;
+            assert!(camera.position() == Vec2::new(100, 100));
+        }
+        assert!(shaken);
+
+        camera.update_effects(20.0, &mut rng);
+        assert!(camera.world_to_screen(Vec2::new(100, 100)) == Vec2::new(320, 240));
+    }
+
+    #[test]
+    fn pick_tile_while_shaking() {
+        let mut camera = camera();
+        let mut rng = Random::new(1);
+        camera.look_at(Vec2::new(100, 100));
+        let point = Vec2::new(316, 236);
+        let tile = camera.screen_to_tile(point, 32);
+
+        camera.shake(8, 100.0);
+        for _ in 0..4 {
+            camera.update_effects(20.0, &mut rng);
+            assert!(camera.screen_to_tile(point, 32) == tile);
+        }
+    }
+
+    #[test]
+    fn fade_level() {
+        let mut camera = camera();
+        let mut rng = Random::new(1);
+        let black = Color::new(0, 0, 0, 0xff);
+
+        camera.fade_to(black, 100.0);
+        camera.update_effects(50.0, &mut rng);
+        assert!((camera.fade_level - 0.5).abs() < 1e-9);
+        assert!((camera.interpolated(0.5).fade_level - 0.25).abs() < 1e-9);
+        camera.update_effects(50.0, &mut rng);
+        assert!(!camera.is_fading());
+        assert!((camera.fade_level - 1.0).abs() < 1e-9);
+
+        camera.fade_from(black, 100.0);
+        camera.update_effects(100.0, &mut rng);
+        assert!(camera.fade_level.abs() < 1e-9);
+        assert!(camera.fade.is_none());
     }
 }
@@ -0,0 +1,137 @@
+//! Struct of handle to entity.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use std::fmt;
+use std::marker::PhantomData;
+
+/// Id of entity.
+///
+/// Slot of removed entity is reused with next generation,
+/// so the id of removed entity never points to another entity.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash, Debug)]
+pub struct ObjectId {
+    index: usize,
+    generation: u32,
+}
+
+impl ObjectId {
+    pub fn new(index: usize, generation: u32) -> Self {
+        ObjectId { index, generation }
+    }
+
+    pub fn index(&self) -> usize {
+        self.index
+    }
+
+    pub fn generation(&self) -> u32 {
+        self.generation
+    }
+}
+
+impl fmt::Display for ObjectId {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "{}v{}", self.index, self.generation)
+    }
+}
+
+/// Id of entity having component `T`.
+pub struct Handle<T> {
+    id: ObjectId,
+    _type: PhantomData<fn() -> T>,
+}
+
+impl<T> Handle<T> {
+    /// Handle is made by `World::handle`, which checks components of the entity.
+    pub(crate) fn new(id: ObjectId) -> Self {
+        Handle {
+            id,
+            _type: PhantomData,
+        }
+    }
+
+    pub fn id(&self) -> ObjectId {
+        self.id
+    }
+}
+
+// Derive requires `T` to implement them
+impl<T> Clone for Handle<T> {
+    fn clone(&self) -> Self {
+        *self
+    }
+}
+
+impl<T> Copy for Handle<T> {}
+
+impl<T> PartialEq for Handle<T> {
+    fn eq(&self, other: &Self) -> bool {
+        self.id == other.id
+    }
+}
+
+impl<T> fmt::Debug for Handle<T> {
+    fn fmt(&self, f: &mut fmt::Formatter) -> fmt::Result {
+        write!(f, "Handle({})", self.id)
+    }
+}
+
+impl<T> From<Handle<T>> for ObjectId {
+    fn from(handle: Handle<T>) -> Self {
+        handle.id
+    }
+}
+
+/// Allocator of ids reusing slots of removed entities.
+#[derive(Default)]
+pub struct IdAllocator {
+    generations: Vec<u32>,
+    alive: Vec<bool>,
+    free: Vec<usize>,
+}
+
+impl IdAllocator {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Reserve id of new entity. It is not alive until `activate` is called.
+    pub fn reserve(&mut self) -> ObjectId {
+        match self.free.pop() {
+            Some(index) => ObjectId::new(index, self.generations[index]),
+            None => {
+                self.generations.push(0);
+                self.alive.push(false);
+                ObjectId::new(self.generations.len() - 1, 0)
+            }
+        }
+    }
+
+    pub fn activate(&mut self, id: ObjectId) {
+        if self.is_current(id) {
+            self.alive[id.index] = true;
+        }
+    }
+
+    /// Free the slot for next generation.
+    pub fn release(&mut self, id: ObjectId) {
+        if !self.is_current(id) {
+            return;
+        }
+        let generation = &mut self.generations[id.index];
+        *generation = generation.wrapping_add(1);
+        self.alive[id.index] = false;
+        self.free.push(id.index);
+    }
+
+    pub fn is_alive(&self, id: ObjectId) -> bool {
+        self.is_current(id) && self.alive[id.index]
+    }
+
+    fn is_current(&self, id: ObjectId) -> bool {
+        self.generations.get(id.index) == Some(&id.generation)
+    }
+}
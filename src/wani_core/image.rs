@@ -0,0 +1,32 @@
+//! Table of images.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+/// Paths of images under `app`, in the order of `res_name` in resource.js.
+///
+/// Index in this table is the image index of `draw_image`.
+pub const PATHS: [&str; 2] = ["./res/mapchip.png", "./res/hero.png"];
+
+/// Tileset of maps.
+pub const MAPCHIP: usize = 0;
+/// Sprite sheet of the hero.
+pub const HERO: usize = 1;
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_order_as_resource_js() {
+        let js = include_str!("../../app/scripts/resource.js");
+        let line = js.lines().find(|l| l.contains("res_name =")).unwrap();
+        let names: Vec<_> = line.split('"').skip(1).step_by(2).collect();
+        assert_eq!(names, PATHS);
+
+        assert!(PATHS[MAPCHIP].ends_with("mapchip.png"));
+        assert!(PATHS[HERO].ends_with("hero.png"));
+    }
+}
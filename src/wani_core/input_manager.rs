@@ -5,12 +5,6 @@
 //! This source code is released under the MIT License
 //! http://opensource.org/licenses/mit-license.php
 
-use std::sync::Mutex;
-
-lazy_static! {
-    pub static ref INPUT_MANAGER: Mutex<InputManager> = Mutex::new(InputManager::new());
-}
-
 pub struct InputManager {
     inputs: Vec<usize>,
 }
@@ -20,6 +14,30 @@ pub enum InputKey {
     DOWN,
     LEFT,
     RIGHT,
+    CONFIRM,
+    CANCEL,
+    INVENTORY,
+}
+
+impl InputKey {
+    /// Return whether the key code is assigned to this key.
+    pub fn matches(&self, key_code: usize) -> bool {
+        match self {
+            InputKey::UP => key_code == 38 || key_code == 87,
+            InputKey::DOWN => key_code == 40 || key_code == 83,
+            InputKey::LEFT => key_code == 37 || key_code == 65,
+            InputKey::RIGHT => key_code == 39 || key_code == 68,
+            InputKey::CONFIRM => key_code == 13 || key_code == 32,
+            InputKey::CANCEL => key_code == 27,
+            InputKey::INVENTORY => key_code == 73,
+        }
+    }
+}
+
+impl Default for InputManager {
+    fn default() -> Self {
+        Self::new()
+    }
 }
 
 impl InputManager {
@@ -36,11 +54,6 @@ impl InputManager {
     }
 
     pub fn get_key(&self, key: InputKey) -> bool {
-        match key {
-            InputKey::UP => self.inputs.contains(&38) || self.inputs.contains(&87),
-            InputKey::DOWN => self.inputs.contains(&40) || self.inputs.contains(&83),
-            InputKey::LEFT => self.inputs.contains(&37) || self.inputs.contains(&65),
-            InputKey::RIGHT => self.inputs.contains(&39) || self.inputs.contains(&68),
-        }
+        self.inputs.iter().any(|&code| key.matches(code))
     }
 }
@@ -0,0 +1,224 @@
+//! Leveled logger with filter per module.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use std::cmp::Reverse;
+use std::fmt;
+use std::sync::Mutex;
+
+use crate::entry;
+
+const CRATE_NAME: &str = "wani_rogue";
+
+lazy_static! {
+    pub static ref LOGGER: Mutex<Logger> = Mutex::new(Logger::new());
+}
+
+#[derive(Clone, Copy, PartialEq, PartialOrd, Debug)]
+pub enum Level {
+    Trace,
+    Debug,
+    Info,
+    Warn,
+    Error,
+    Off,
+}
+
+impl Level {
+    /// Convert from number given by JavaScript. (0: Trace .. 5: Off)
+    pub fn from_usize(level: usize) -> Self {
+        match level {
+            0 => Level::Trace,
+            1 => Level::Debug,
+            2 => Level::Info,
+            3 => Level::Warn,
+            4 => Level::Error,
+            _ => Level::Off,
+        }
+    }
+
+    pub fn parse(level: &str) -> Option<Self> {
+        match level.trim().to_ascii_lowercase().as_str() {
+            "trace" => Some(Level::Trace),
+            "debug" => Some(Level::Debug),
+            "info" => Some(Level::Info),
+            "warn" => Some(Level::Warn),
+            "error" => Some(Level::Error),
+            "off" => Some(Level::Off),
+            _ => None,
+        }
+    }
+
+    fn name(self) -> &'static str {
+        match self {
+            Level::Trace => "TRACE",
+            Level::Debug => "DEBUG",
+            Level::Info => "INFO",
+            Level::Warn => "WARN",
+            Level::Error => "ERROR",
+            Level::Off => "OFF",
+        }
+    }
+}
+
+pub struct Logger {
+    level: Level,
+    filters: Vec<(String, Level)>,
+}
+
+impl Default for Logger {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Logger {
+    pub fn new() -> Self {
+        Logger {
+            level: Level::Info,
+            filters: Vec::new(),
+        }
+    }
+
+    /// Set threshold for modules without filter.
+    pub fn set_level(&mut self, level: Level) {
+        self.level = level;
+    }
+
+    /// Set filter like `warn,wani_map=trace`.
+    ///
+    /// A bare level sets the threshold and `module=level` sets it for the module and its children.
+    /// The module path may omit the crate name.
+    pub fn set_filter(&mut self, filter: &str) {
+        self.filters.clear();
+        for directive in filter.split(',').map(str::trim).filter(|d| !d.is_empty()) {
+            match directive.find('=') {
+                Some(i) => {
+                    if let Some(level) = Level::parse(&directive[i + 1..]) {
+                        self.filters.push((full_path(directive[..i].trim()), level));
+                    }
+                }
+                None => {
+                    if let Some(level) = Level::parse(directive) {
+                        self.level = level;
+                    }
+                }
+            }
+        }
+        // Most specific module is checked first
+        self.filters.sort_by_key(|f| Reverse(f.0.len()));
+    }
+
+    /// Return whether the log of the module is output.
+    pub fn enabled(&self, module: &str, level: Level) -> bool {
+        let threshold = self
+            .filters
+            .iter()
+            .find(|(m, _)| {
+                module == m || module.starts_with(m.as_str()) && module[m.len()..].starts_with("::")
+            })
+            .map_or(self.level, |(_, l)| *l);
+        level != Level::Off && threshold <= level
+    }
+}
+
+/// Prepend the crate name unless the path already starts with it.
+fn full_path(module: &str) -> String {
+    if module == CRATE_NAME || module.starts_with(&format!("{}::", CRATE_NAME)) {
+        module.to_string()
+    } else {
+        format!("{}::{}", CRATE_NAME, module)
+    }
+}
+
+/// Output log through `entry::log` if it is enabled. Called from macros.
+pub fn log(level: Level, module: &str, args: fmt::Arguments) {
+    if LOGGER.lock().unwrap().enabled(module, level) {
+        entry::log(&format!("[{} {}] {}", level.name(), module, args));
+    }
+}
+
+#[macro_export]
+macro_rules! trace {
+    ($($arg:tt)+) => {
+        $crate::wani_core::logger::log(
+            $crate::wani_core::logger::Level::Trace,
+            module_path!(),
+            format_args!($($arg)+),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! debug {
+    ($($arg:tt)+) => {
+        $crate::wani_core::logger::log(
+            $crate::wani_core::logger::Level::Debug,
+            module_path!(),
+            format_args!($($arg)+),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! info {
+    ($($arg:tt)+) => {
+        $crate::wani_core::logger::log(
+            $crate::wani_core::logger::Level::Info,
+            module_path!(),
+            format_args!($($arg)+),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! warn {
+    ($($arg:tt)+) => {
+        $crate::wani_core::logger::log(
+            $crate::wani_core::logger::Level::Warn,
+            module_path!(),
+            format_args!($($arg)+),
+        )
+    };
+}
+
+#[macro_export]
+macro_rules! error {
+    ($($arg:tt)+) => {
+        $crate::wani_core::logger::log(
+            $crate::wani_core::logger::Level::Error,
+            module_path!(),
+            format_args!($($arg)+),
+        )
+    };
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn filter_by_module() {
+        let mut logger = Logger::new();
+        assert!(logger.enabled("wani_rogue::wani_map", Level::Info));
+        assert!(!logger.enabled("wani_rogue::wani_map", Level::Debug));
+
+        logger.set_filter("warn, wani_map=trace, wani_rogue::wani_map::random_map=error");
+        assert!(!logger.enabled("wani_rogue::entry", Level::Info));
+        assert!(logger.enabled("wani_rogue::entry", Level::Warn));
+        assert!(logger.enabled("wani_rogue::wani_map", Level::Trace));
+        assert!(logger.enabled("wani_rogue::wani_map::map_component", Level::Trace));
+        assert!(!logger.enabled("wani_rogue::wani_map::random_map", Level::Warn));
+        assert!(!logger.enabled("wani_rogue::wani_mapper", Level::Info));
+
+        logger.set_level(Level::Off);
+        assert!(!logger.enabled("wani_rogue::entry", Level::Error));
+
+        logger.set_filter("wani_rogue=trace");
+        assert!(logger.enabled("wani_rogue::entry", Level::Trace));
+        assert!(logger.enabled("wani_rogue::wani_map::random_map", Level::Trace));
+    }
+}
@@ -0,0 +1,40 @@
+//! Panic hook reporting to the host.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use std::panic;
+use std::sync::atomic::{AtomicBool, Ordering};
+
+use crate::wani_platform::report;
+
+static HALTED: AtomicBool = AtomicBool::new(false);
+
+/// Set hook to log panic message and location and halt the game.
+pub fn install() {
+    panic::set_hook(Box::new(|info| {
+        HALTED.store(true, Ordering::SeqCst);
+
+        let payload = info.payload();
+        let message = if let Some(s) = payload.downcast_ref::<&str>() {
+            s
+        } else if let Some(s) = payload.downcast_ref::<String>() {
+            s.as_str()
+        } else {
+            "Box<Any>"
+        };
+        let location = match info.location() {
+            Some(l) => format!("{}:{}:{}", l.file(), l.line(), l.column()),
+            None => String::from("unknown location"),
+        };
+
+        report(&format!("[PANIC] {} at {}", message, location));
+    }));
+}
+
+/// Return whether the game is halted by panic.
+pub fn is_halted() -> bool {
+    HALTED.load(Ordering::SeqCst)
+}
@@ -0,0 +1,120 @@
+//! Struct of seedable random number generator.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use std::ops::Range;
+
+/// Purpose of random number stream.
+///
+/// Each stream is independent, so rolls in one system do not change others.
+#[derive(Clone, Copy)]
+pub enum RandomStream {
+    Map,
+    Combat,
+    Cosmetic,
+}
+
+/// Deterministic pseudo random number generator (xorshift64*).
+///
+/// Same seed always generates same sequence on every platform.
+#[derive(Clone)]
+pub struct Random {
+    seed: u32,
+    state: u64,
+}
+
+impl Random {
+    pub fn new(seed: u32) -> Self {
+        Random::with_key(seed, 0)
+    }
+
+    /// Derive generator of the stream from seed of run.
+    pub fn derive(seed: u32, stream: RandomStream) -> Self {
+        Random::with_key(seed, stream as u64 + 1)
+    }
+
+    fn with_key(seed: u32, key: u64) -> Self {
+        // Spread the seed and key over the whole state by splitmix64
+        let mut z = (seed as u64 | key << 32).wrapping_add(0x9e37_79b9_7f4a_7c15);
+        z = (z ^ (z >> 30)).wrapping_mul(0xbf58_476d_1ce4_e5b9);
+        z = (z ^ (z >> 27)).wrapping_mul(0x94d0_49bb_1331_11eb);
+        z ^= z >> 31;
+
+        Random {
+            seed,
+            // xorshift must not start from zero
+            state: if z == 0 { 1 } else { z },
+        }
+    }
+
+    /// Return seed of this generator.
+    pub fn seed(&self) -> u32 {
+        self.seed
+    }
+
+    /// Generate next 32 bit random number.
+    pub fn next_u32(&mut self) -> u32 {
+        self.state ^= self.state >> 12;
+        self.state ^= self.state << 25;
+        self.state ^= self.state >> 27;
+        (self.state.wrapping_mul(0x2545_f491_4f6c_dd1d) >> 32) as u32
+    }
+
+    /// Generate random numbers from range.
+    pub fn range(&mut self, range: Range<isize>) -> isize {
+        let len = (range.end - range.start) as u64;
+        if len == 0 {
+            return range.start;
+        }
+        (self.next_u32() as u64 % len) as isize + range.start
+    }
+
+    /// Generate random numbers from range<usize>.
+    pub fn urange(&mut self, range: Range<usize>) -> usize {
+        let len = (range.end - range.start) as u64;
+        if len == 0 {
+            return range.start;
+        }
+        (self.next_u32() as u64 % len) as usize + range.start
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn same_seed_same_sequence() {
+        let mut a = Random::new(12345);
+        let mut b = Random::new(12345);
+        for _ in 0..100 {
+            assert_eq!(a.next_u32(), b.next_u32());
+        }
+        assert_eq!(a.seed(), 12345);
+    }
+
+    #[test]
+    fn streams_are_independent() {
+        let mut map = Random::derive(12345, RandomStream::Map);
+        let mut combat = Random::derive(12345, RandomStream::Combat);
+        assert_eq!(map.seed(), combat.seed());
+
+        let a: Vec<u32> = (0..8).map(|_| map.next_u32()).collect();
+        let b: Vec<u32> = (0..8).map(|_| combat.next_u32()).collect();
+        assert!(a != b);
+    }
+
+    #[test]
+    fn range_is_in_bounds() {
+        let mut r = Random::new(1);
+        for _ in 0..1000 {
+            let n = r.range(-3..5);
+            assert!((-3..5).contains(&n));
+            let u = r.urange(2..4);
+            assert!((2..4).contains(&u));
+        }
+    }
+}
@@ -40,8 +40,8 @@ impl Rect {
 
    pub fn center(&self) -> Vec2 {
       Vec2 {
-         x: (self.x + self.w as isize) / 2,
-         y: (self.y + self.h as isize) / 2,
+         x: self.x + self.w as isize / 2,
+         y: self.y + self.h as isize / 2,
       }
    }
 
@@ -51,11 +51,4 @@ impl Rect {
          && self.top() <= point.y
          && point.y <= self.bottom()
    }
-
-   pub fn contains_rect(&self, rect: Rect) -> bool {
-      self.left() <= rect.left() && rect.left() <= self.right()
-         || self.left() <= rect.right() && rect.right() <= self.right()
-         || self.top() <= rect.top() && rect.top() <= self.bottom()
-         || self.top() <= rect.bottom() && rect.bottom() <= self.bottom()
-   }
 }
@@ -0,0 +1,135 @@
+//! Struct of turn scheduler.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::wani_core::handle::ObjectId;
+
+/// Energy needed to act.
+pub const ENERGY_TO_ACT: usize = 100;
+/// Speed acting once per turn.
+pub const NORMAL_SPEED: usize = 100;
+
+struct Entry {
+    id: ObjectId,
+    speed: usize,
+    energy: usize,
+}
+
+/// Energy based scheduler of actors.
+///
+/// Each turn every actor gains energy by its speed,
+/// and the actor which has enough energy acts.
+#[derive(Default)]
+pub struct TurnScheduler {
+    actors: Vec<Entry>,
+    turn: u64,
+}
+
+impl TurnScheduler {
+    pub fn new() -> Self {
+        TurnScheduler {
+            actors: Vec::new(),
+            turn: 0,
+        }
+    }
+
+    /// Add actor of the game object. It acts on next turn.
+    pub fn add(&mut self, id: ObjectId, speed: usize) {
+        self.remove(id);
+        self.actors.push(Entry {
+            id,
+            speed,
+            energy: 0,
+        });
+    }
+
+    pub fn remove(&mut self, id: ObjectId) {
+        self.actors.retain(|a| a.id != id);
+    }
+
+    pub fn contains(&self, id: ObjectId) -> bool {
+        self.actors.iter().any(|a| a.id == id)
+    }
+
+    /// Return number of elapsed turns.
+    pub fn turn(&self) -> u64 {
+        self.turn
+    }
+
+    /// Return id of actor to act next, advancing turns until someone can act.
+    ///
+    /// Same actor is returned until `spend` is called.
+    pub fn next_actor(&mut self) -> Option<ObjectId> {
+        if self.actors.iter().all(|a| a.speed == 0) {
+            return None;
+        }
+
+        loop {
+            // Most energetic actor first, and registered order for tie
+            let mut ready: Option<&Entry> = None;
+            for a in &self.actors {
+                if ENERGY_TO_ACT <= a.energy && ready.map_or(true, |r| r.energy < a.energy) {
+                    ready = Some(a);
+                }
+            }
+            if let Some(a) = ready {
+                return Some(a.id);
+            }
+
+            for a in &mut self.actors {
+                a.energy += a.speed;
+            }
+            self.turn += 1;
+        }
+    }
+
+    /// Consume energy of the actor for its action.
+    pub fn spend(&mut self, id: ObjectId, cost: usize) {
+        if let Some(a) = self.actors.iter_mut().find(|a| a.id == id) {
+            a.energy = a.energy.saturating_sub(cost);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn fast_actor_acts_more() {
+        let (normal, fast) = (ObjectId::new(1, 0), ObjectId::new(2, 0));
+        let mut scheduler = TurnScheduler::new();
+        scheduler.add(normal, NORMAL_SPEED);
+        scheduler.add(fast, NORMAL_SPEED * 2);
+
+        let mut acts = Vec::new();
+        while scheduler.turn() < 4 || acts.len() % 3 != 0 {
+            let id = scheduler.next_actor().unwrap();
+            acts.push(id);
+            scheduler.spend(id, ENERGY_TO_ACT);
+        }
+        assert_eq!(acts.iter().filter(|&&id| id == normal).count(), 4);
+        assert_eq!(acts.iter().filter(|&&id| id == fast).count(), 8);
+    }
+
+    #[test]
+    fn wait_for_actor() {
+        let id = ObjectId::new(1, 0);
+        let mut scheduler = TurnScheduler::new();
+        scheduler.add(id, NORMAL_SPEED);
+
+        assert_eq!(scheduler.next_actor(), Some(id));
+        assert_eq!(scheduler.turn(), 1);
+
+        // The world does not advance until the actor spends energy
+        assert_eq!(scheduler.next_actor(), Some(id));
+        assert_eq!(scheduler.turn(), 1);
+
+        scheduler.spend(id, ENERGY_TO_ACT);
+        assert_eq!(scheduler.next_actor(), Some(id));
+        assert_eq!(scheduler.turn(), 2);
+    }
+}
@@ -0,0 +1,88 @@
+//! Struct of tile metrics.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::wani_core::rect::Rect;
+use crate::wani_core::vector2::Vec2;
+
+/// Sizes of tile in tileset image and on screen.
+#[derive(Clone, Copy)]
+pub struct TileMetrics {
+    /// Size of a tile in tileset image
+    pub source_size: usize,
+    /// Size of a tile on screen
+    pub screen_size: usize,
+    /// Number of tiles in a row of tileset image
+    pub atlas_columns: usize,
+}
+
+impl Default for TileMetrics {
+    fn default() -> Self {
+        TileMetrics {
+            source_size: 32,
+            screen_size: 32,
+            atlas_columns: 8,
+        }
+    }
+}
+
+impl TileMetrics {
+    /// Return `None` if any of the values is 0.
+    pub fn new(source_size: usize, screen_size: usize, atlas_columns: usize) -> Option<Self> {
+        if source_size == 0 || screen_size == 0 || atlas_columns == 0 {
+            return None;
+        }
+        Some(TileMetrics {
+            source_size,
+            screen_size,
+            atlas_columns,
+        })
+    }
+
+    /// Return rect of the tile in tileset image.
+    pub fn source_rect(&self, index: usize) -> Rect {
+        let s = self.source_size;
+        Rect::new(
+            (index % self.atlas_columns * s) as isize,
+            (index / self.atlas_columns * s) as isize,
+            s,
+            s,
+        )
+    }
+
+    /// Return pixel position of the tile coordinate.
+    pub fn to_pixel(&self, coord: Vec2) -> Vec2 {
+        coord * self.screen_size as isize
+    }
+
+    /// Return rect of the tile coordinate on screen.
+    pub fn screen_rect(&self, coord: Vec2, offset: Vec2) -> Rect {
+        let pos = self.to_pixel(coord) + offset;
+        Rect::new(pos.x, pos.y, self.screen_size, self.screen_size)
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn atlas_lookup() {
+        let metrics = TileMetrics::new(16, 48, 4).unwrap();
+        let src = metrics.source_rect(6);
+        assert_eq!((src.x, src.y, src.w, src.h), (32, 16, 16, 16));
+
+        let dist = metrics.screen_rect(Vec2::new(2, 3), Vec2::new(-10, 5));
+        assert_eq!((dist.x, dist.y, dist.w, dist.h), (86, 149, 48, 48));
+    }
+
+    #[test]
+    fn reject_zero() {
+        assert!(TileMetrics::new(0, 32, 8).is_none());
+        assert!(TileMetrics::new(32, 0, 8).is_none());
+        assert!(TileMetrics::new(32, 32, 0).is_none());
+    }
+}
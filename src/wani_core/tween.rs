@@ -0,0 +1,176 @@
+//! Struct of tween.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+/// Curve of progress over time.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Easing {
+    Linear,
+    InQuad,
+    OutQuad,
+    InOutQuad,
+    InCubic,
+    OutCubic,
+    InOutCubic,
+    /// Overshoot the end a little and come back.
+    OutBack,
+}
+
+impl Easing {
+    /// Return eased progress of `t` in `0.0..=1.0`.
+    pub fn apply(self, t: f64) -> f64 {
+        match self {
+            Easing::Linear => t,
+            Easing::InQuad => t * t,
+            Easing::OutQuad => 1.0 - (1.0 - t) * (1.0 - t),
+            Easing::InOutQuad => {
+                if t < 0.5 {
+                    2.0 * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(2) / 2.0
+                }
+            }
+            Easing::InCubic => t * t * t,
+            Easing::OutCubic => 1.0 - (1.0 - t).powi(3),
+            Easing::InOutCubic => {
+                if t < 0.5 {
+                    4.0 * t * t * t
+                } else {
+                    1.0 - (-2.0 * t + 2.0).powi(3) / 2.0
+                }
+            }
+            Easing::OutBack => {
+                let c1 = 1.70158;
+                let c3 = c1 + 1.0;
+                1.0 + c3 * (t - 1.0).powi(3) + c1 * (t - 1.0).powi(2)
+            }
+        }
+    }
+}
+
+/// Value which can be tweened.
+pub trait Lerp: Copy {
+    /// Return the value at `t` from self to `to`. `t` may be out of `0.0..=1.0`.
+    fn lerp(self, to: Self, t: f64) -> Self;
+}
+
+impl Lerp for f64 {
+    fn lerp(self, to: Self, t: f64) -> Self {
+        self + (to - self) * t
+    }
+}
+
+impl Lerp for (f64, f64) {
+    fn lerp(self, to: Self, t: f64) -> Self {
+        (self.0.lerp(to.0, t), self.1.lerp(to.1, t))
+    }
+}
+
+/// Change of a value over time. Times are in milliseconds.
+///
+/// Advanced by `update` every update step, and drawn by `value_at`
+/// between last and current step like other interpolated values.
+#[derive(Clone, Copy)]
+pub struct Tween<T> {
+    from: T,
+    to: T,
+    easing: Easing,
+
+    duration: f64,
+    finished: bool,
+    elapsed: f64,
+    prev_elapsed: f64,
+}
+
+impl<T: Lerp> Tween<T> {
+    pub fn new(from: T, to: T, duration: f64, easing: Easing) -> Self {
+        Tween {
+            from,
+            to,
+            easing,
+
+            duration,
+            finished: false,
+            elapsed: 0.0,
+            prev_elapsed: 0.0,
+        }
+    }
+
+    /// Advance by elapsed milliseconds. Return true at the step finishing the tween.
+    pub fn update(&mut self, elapsed: f64) -> bool {
+        self.prev_elapsed = self.elapsed;
+        if self.finished {
+            return false;
+        }
+        self.elapsed = (self.elapsed + elapsed).min(self.duration.max(0.0));
+        self.finished = self.duration <= self.elapsed;
+        self.finished
+    }
+
+    pub fn is_finished(&self) -> bool {
+        self.finished
+    }
+
+    pub fn to(&self) -> T {
+        self.to
+    }
+
+    /// Return the value at current update step.
+    pub fn value(&self) -> T {
+        self.sample(self.elapsed)
+    }
+
+    /// Return the value between last and current update step.
+    pub fn value_at(&self, alpha: f64) -> T {
+        self.sample(self.prev_elapsed + (self.elapsed - self.prev_elapsed) * alpha)
+    }
+
+    fn sample(&self, elapsed: f64) -> T {
+        let t = if self.duration <= 0.0 {
+            1.0
+        } else {
+            (elapsed / self.duration).clamp(0.0, 1.0)
+        };
+        self.from.lerp(self.to, self.easing.apply(t))
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn easing_ends() {
+        let all = [
+            Easing::Linear,
+            Easing::InQuad,
+            Easing::OutQuad,
+            Easing::InOutQuad,
+            Easing::InCubic,
+            Easing::OutCubic,
+            Easing::InOutCubic,
+            Easing::OutBack,
+        ];
+        for easing in all.iter() {
+            assert!(easing.apply(0.0).abs() < 1e-9, "{:?}", easing);
+            assert!((easing.apply(1.0) - 1.0).abs() < 1e-9, "{:?}", easing);
+        }
+        assert!(Easing::InQuad.apply(0.5) < 0.5 && 0.5 < Easing::OutQuad.apply(0.5));
+    }
+
+    #[test]
+    fn finish_once() {
+        let mut tween = Tween::new(0.0, 10.0, 100.0, Easing::Linear);
+        assert!(!tween.update(60.0));
+        assert!((tween.value() - 6.0).abs() < 1e-9);
+        assert!((tween.value_at(0.5) - 3.0).abs() < 1e-9);
+
+        assert!(tween.update(60.0));
+        assert!(tween.is_finished());
+        assert!((tween.value() - 10.0).abs() < 1e-9);
+        assert!(!tween.update(60.0));
+    }
+}
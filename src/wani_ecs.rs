@@ -1,10 +1,12 @@
-//! Trait of canvas drawer
+//! Module for entity component system.
 //!
 //! Copyright (c) 2019 wanigame
 //!
 //! This source code is released under the MIT License
 //! http://opensource.org/licenses/mit-license.php
 
-pub trait Drawer {
-    fn draw(&self);
-}
+pub mod component;
+pub mod event;
+pub mod storage;
+pub mod system;
+pub mod world;
@@ -0,0 +1,206 @@
+//! Components of entity.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::wani_character::sprite_sheet::{Facing, Pose, SpriteSheet};
+use crate::wani_core::color::Color;
+use crate::wani_core::rect::Rect;
+use crate::wani_core::tween::{Easing, Tween};
+use crate::wani_core::vector2;
+use crate::wani_core::vector2::Vec2;
+use crate::wani_map::random_map::RandomMap;
+
+/// Time to move to next tile in milliseconds.
+pub const MOVE_DURATION: f64 = 160.0;
+
+/// Layer of drawing. Former layer is drawn under latter one.
+#[derive(Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Debug)]
+pub enum RenderLayer {
+    Floor,
+    Item,
+    Actor,
+    Effect,
+    Ui,
+}
+
+/// Coordinate of tile on the map.
+#[derive(Clone, Copy)]
+pub struct Position {
+    pub coord: Vec2,
+}
+
+impl Position {
+    pub fn new(coord: Vec2) -> Self {
+        Position { coord }
+    }
+}
+
+/// Visual offset from the tile while moving.
+pub struct Motion {
+    /// Offset in tiles
+    offset: Option<Tween<(f64, f64)>>,
+}
+
+impl Default for Motion {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Motion {
+    pub fn new() -> Self {
+        Motion { offset: None }
+    }
+
+    /// Stop at the tile immediately.
+    pub fn stop(&mut self) {
+        self.offset = None;
+    }
+
+    /// Start animation from previous tile after the tile coordinate is moved by `move_dir`.
+    pub fn start_move(&mut self, move_dir: Vec2) {
+        let from = (-move_dir.x as f64, -move_dir.y as f64);
+        self.offset = Some(Tween::new(from, (0.0, 0.0), MOVE_DURATION, Easing::Linear));
+    }
+
+    /// Advance animation by elapsed milliseconds. Return true when it finishes.
+    pub fn animate(&mut self, elapsed: f64) -> bool {
+        match &mut self.offset {
+            Some(tween) => tween.update(elapsed),
+            None => false,
+        }
+    }
+
+    pub fn is_animating(&self) -> bool {
+        self.offset.is_some_and(|t| !t.is_finished())
+    }
+
+    /// Return pixel offset interpolated between last and current update step.
+    pub fn pixel_offset(&self, tile_size: usize, alpha: f64) -> Vec2 {
+        let (x, y) = match &self.offset {
+            Some(tween) => tween.value_at(alpha),
+            None => return vector2::ZERO,
+        };
+        let size = tile_size as f64;
+        Vec2::new((x * size).round() as isize, (y * size).round() as isize)
+    }
+}
+
+/// Place of entity in the order of drawing.
+///
+/// Sorted by layer, z-order and then tile row, so lower tile is drawn in front.
+#[derive(Clone, Copy)]
+pub struct RenderOrder {
+    pub layer: RenderLayer,
+    pub z_order: isize,
+}
+
+impl RenderOrder {
+    pub fn new(layer: RenderLayer) -> Self {
+        RenderOrder { layer, z_order: 0 }
+    }
+}
+
+/// Appearance of entity on the tile.
+#[derive(Clone, Copy)]
+pub struct Sprite {
+    pub color: Color,
+}
+
+impl Sprite {
+    pub fn new(color: Color) -> Self {
+        Sprite { color }
+    }
+}
+
+/// Frames of sprite sheet changing by facing and pose.
+///
+/// Drawn instead of the color of `Sprite`.
+pub struct Animator {
+    pub sheet: &'static SpriteSheet,
+    pub facing: Facing,
+    pose: Pose,
+    /// Time in current pose in milliseconds
+    elapsed: f64,
+}
+
+impl Animator {
+    pub fn new(sheet: &'static SpriteSheet) -> Self {
+        Animator {
+            sheet,
+            facing: Facing::Down,
+            pose: Pose::Idle,
+            elapsed: 0.0,
+        }
+    }
+
+    /// Face to the direction. Zero direction keeps current facing.
+    pub fn face(&mut self, dir: Vec2) {
+        if let Some(facing) = Facing::from_dir(dir) {
+            self.facing = facing;
+        }
+    }
+
+    /// Change the pose and start its clip from the first frame.
+    pub fn set_pose(&mut self, pose: Pose) {
+        if self.pose != pose {
+            self.pose = pose;
+            self.elapsed = 0.0;
+        }
+    }
+
+    pub fn pose(&self) -> Pose {
+        self.pose
+    }
+
+    /// Advance the clip by elapsed milliseconds.
+    pub fn update(&mut self, elapsed: f64) {
+        self.elapsed += elapsed;
+    }
+
+    /// Return rect of current frame in the image of the sheet.
+    pub fn source_rect(&self) -> Rect {
+        let clip = self.sheet.clip(self.pose, self.facing);
+        self.sheet.source_rect(clip.frame_at(self.elapsed))
+    }
+}
+
+/// Behavior of actor not controlled by the player.
+#[derive(Clone, Copy, PartialEq, Debug)]
+pub enum Ai {
+    /// Do nothing but spend the turn.
+    Wait,
+}
+
+/// Marker of entity controlled by input.
+#[derive(Clone, Copy)]
+pub struct Player;
+
+/// Entity taking turns in the scheduler.
+#[derive(Clone, Copy)]
+pub struct Actor {
+    pub speed: usize,
+}
+
+impl Actor {
+    pub fn new(speed: usize) -> Self {
+        Actor { speed }
+    }
+}
+
+/// Set of components to spawn an entity.
+#[derive(Default)]
+pub struct Components {
+    pub position: Option<Position>,
+    pub motion: Option<Motion>,
+    pub render_order: Option<RenderOrder>,
+    pub sprite: Option<Sprite>,
+    pub animator: Option<Animator>,
+    pub ai: Option<Ai>,
+    pub player: Option<Player>,
+    pub actor: Option<Actor>,
+    pub map: Option<RandomMap>,
+}
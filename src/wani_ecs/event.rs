@@ -0,0 +1,183 @@
+//! Events between entities.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use std::mem;
+
+use crate::wani_core::handle::{Handle, ObjectId};
+use crate::wani_core::vector2::Vec2;
+use crate::wani_map::random_map::RandomMap;
+
+/// Entity was added to the world.
+#[derive(Clone, Copy)]
+pub struct Spawned {
+    pub entity: ObjectId,
+}
+
+/// Entity was removed from the world.
+#[derive(Clone, Copy)]
+pub struct Despawned {
+    pub entity: ObjectId,
+}
+
+/// Entity walked to next tile.
+#[derive(Clone, Copy)]
+pub struct Moved {
+    pub entity: ObjectId,
+    pub from: Vec2,
+    pub to: Vec2,
+}
+
+/// Motion of entity reached its end.
+#[derive(Clone, Copy)]
+pub struct AnimationFinished {
+    pub entity: ObjectId,
+}
+
+/// The hero entered a floor.
+#[derive(Clone, Copy)]
+pub struct FloorChanged {
+    pub map: Handle<RandomMap>,
+}
+
+/// Id to cancel a subscription.
+#[derive(Clone, Copy, PartialEq, Eq, Debug)]
+pub struct Subscription(usize);
+
+type Handler<T> = Box<dyn FnMut(&T)>;
+
+/// Queue of events of a type and handlers of them.
+///
+/// Published events are kept until `deliver`,
+/// so publisher never runs handlers in the middle of its work.
+/// Systems needing the world read delivered events by `read` instead of subscribing.
+pub struct Channel<T> {
+    pending: Vec<T>,
+    delivered: Vec<T>,
+    handlers: Vec<(Subscription, Handler<T>)>,
+    next_id: usize,
+}
+
+impl<T> Default for Channel<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Channel<T> {
+    pub fn new() -> Self {
+        Channel {
+            pending: Vec::new(),
+            delivered: Vec::new(),
+            handlers: Vec::new(),
+            next_id: 0,
+        }
+    }
+
+    /// Queue the event to be delivered.
+    pub fn publish(&mut self, event: T) {
+        self.pending.push(event);
+    }
+
+    /// Call `handler` with every event delivered from now.
+    pub fn subscribe<F>(&mut self, handler: F) -> Subscription
+    where
+        F: FnMut(&T) + 'static,
+    {
+        let id = Subscription(self.next_id);
+        self.next_id += 1;
+        self.handlers.push((id, Box::new(handler)));
+        id
+    }
+
+    /// Stop calling the handler. Return whether it was subscribed.
+    pub fn unsubscribe(&mut self, id: Subscription) -> bool {
+        let len = self.handlers.len();
+        self.handlers.retain(|(i, _)| *i != id);
+        self.handlers.len() != len
+    }
+
+    /// Pass queued events to handlers in the order of publishing and subscribing.
+    ///
+    /// Events delivered last time are dropped.
+    pub fn deliver(&mut self) {
+        self.delivered.clear();
+        mem::swap(&mut self.pending, &mut self.delivered);
+        for event in &self.delivered {
+            for (_, handler) in self.handlers.iter_mut() {
+                handler(event);
+            }
+        }
+    }
+
+    /// Return events of last `deliver` in the order of publishing.
+    pub fn read(&self) -> &[T] {
+        &self.delivered
+    }
+}
+
+/// Channels of every event.
+///
+/// `Game` delivers events once per update step after actions and spawns of the step.
+#[derive(Default)]
+pub struct EventBus {
+    pub spawned: Channel<Spawned>,
+    pub despawned: Channel<Despawned>,
+    pub moved: Channel<Moved>,
+    pub floor_changed: Channel<FloorChanged>,
+    pub animation_finished: Channel<AnimationFinished>,
+}
+
+impl EventBus {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Deliver queued events of all channels.
+    pub fn deliver(&mut self) {
+        self.spawned.deliver();
+        self.despawned.deliver();
+        self.moved.deliver();
+        self.floor_changed.deliver();
+        self.animation_finished.deliver();
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use std::cell::RefCell;
+    use std::rc::Rc;
+
+    #[test]
+    fn deliver_on_demand() {
+        let mut channel = Channel::new();
+        let log = Rc::new(RefCell::new(Vec::new()));
+
+        let l = log.clone();
+        let first = channel.subscribe(move |e: &usize| l.borrow_mut().push((1, *e)));
+        let l = log.clone();
+        channel.subscribe(move |e: &usize| l.borrow_mut().push((2, *e)));
+
+        channel.publish(10);
+        channel.publish(20);
+        assert!(log.borrow().is_empty());
+
+        channel.deliver();
+        assert_eq!(*log.borrow(), vec![(1, 10), (2, 10), (1, 20), (2, 20)]);
+        assert_eq!(channel.read(), [10, 20]);
+
+        // Delivered events are not delivered again
+        assert!(channel.unsubscribe(first));
+        assert!(!channel.unsubscribe(first));
+        channel.publish(30);
+        channel.deliver();
+        assert_eq!(channel.read(), [30]);
+        channel.deliver();
+        assert_eq!(log.borrow()[4..], [(2, 30)]);
+        assert!(channel.read().is_empty());
+    }
+}
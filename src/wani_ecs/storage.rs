@@ -0,0 +1,77 @@
+//! Struct of component storage.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::wani_core::handle::ObjectId;
+
+/// Components of a type indexed by entity.
+///
+/// Component of removed entity is never returned for the entity of next generation.
+pub struct Storage<T> {
+    slots: Vec<Option<(u32, T)>>,
+}
+
+impl<T> Default for Storage<T> {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl<T> Storage<T> {
+    pub fn new() -> Self {
+        Storage { slots: Vec::new() }
+    }
+
+    /// Attach the component to the entity, and return the old one.
+    pub fn insert(&mut self, entity: ObjectId, component: T) -> Option<T> {
+        if self.slots.len() <= entity.index() {
+            self.slots.resize_with(entity.index() + 1, || None);
+        }
+        let old = self.slots[entity.index()].replace((entity.generation(), component));
+        old.filter(|(g, _)| *g == entity.generation())
+            .map(|(_, c)| c)
+    }
+
+    pub fn remove(&mut self, entity: ObjectId) -> Option<T> {
+        if !self.contains(entity) {
+            return None;
+        }
+        self.slots[entity.index()].take().map(|(_, c)| c)
+    }
+
+    pub fn contains(&self, entity: ObjectId) -> bool {
+        self.get(entity).is_some()
+    }
+
+    pub fn get(&self, entity: ObjectId) -> Option<&T> {
+        match self.slots.get(entity.index()) {
+            Some(Some((g, c))) if *g == entity.generation() => Some(c),
+            _ => None,
+        }
+    }
+
+    pub fn get_mut(&mut self, entity: ObjectId) -> Option<&mut T> {
+        match self.slots.get_mut(entity.index()) {
+            Some(Some((g, c))) if *g == entity.generation() => Some(c),
+            _ => None,
+        }
+    }
+
+    /// Iterate over components in the order of entity index.
+    pub fn iter(&self) -> impl Iterator<Item = (ObjectId, &T)> {
+        self.slots
+            .iter()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.as_ref().map(|(g, c)| (ObjectId::new(i, *g), c)))
+    }
+
+    pub fn iter_mut(&mut self) -> impl Iterator<Item = (ObjectId, &mut T)> {
+        self.slots
+            .iter_mut()
+            .enumerate()
+            .filter_map(|(i, slot)| slot.as_mut().map(|(g, c)| (ObjectId::new(i, *g), c)))
+    }
+}
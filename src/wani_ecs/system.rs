@@ -0,0 +1,304 @@
+//! Systems running over components.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::trace;
+
+use crate::entry::{draw_image, draw_rect};
+use crate::wani_character::sprite_sheet::Pose;
+use crate::wani_core::camera::Camera;
+use crate::wani_core::handle::{Handle, ObjectId};
+use crate::wani_core::random::Random;
+use crate::wani_core::rect::Rect;
+use crate::wani_core::scheduler::ENERGY_TO_ACT;
+use crate::wani_core::tile_metrics::TileMetrics;
+use crate::wani_core::vector2;
+use crate::wani_core::vector2::Vec2;
+use crate::wani_ecs::event::{AnimationFinished, Moved};
+use crate::wani_ecs::world::World;
+use crate::wani_map::map_component::MapComponent;
+use crate::wani_map::random_map::RandomMap;
+
+/// Advance animation of all motions and sprites by elapsed milliseconds. Called every update step.
+pub fn animate(world: &mut World, elapsed: f64) {
+    for (entity, motion) in world.motions.iter_mut() {
+        if motion.animate(elapsed) {
+            world
+                .events
+                .animation_finished
+                .publish(AnimationFinished { entity });
+        }
+    }
+    for (_, animator) in world.animators.iter_mut() {
+        animator.update(elapsed);
+    }
+}
+
+/// Choose poses of sprites from their motions. Called after actions of the update step.
+///
+/// Walk cycle goes on without a break when next move starts at the end of a move.
+pub fn update_poses(world: &mut World) {
+    for (entity, animator) in world.animators.iter_mut() {
+        let moving = world.motions.get(entity).is_some_and(|m| m.is_animating());
+        animator.set_pose(if moving { Pose::Walk } else { Pose::Idle });
+    }
+}
+
+/// Return whether an actor can stand on the tile of the floor map.
+pub fn is_walkable(world: &World, map: Handle<RandomMap>, coord: Vec2) -> bool {
+    let map = match world.get(map) {
+        Some(map) => map,
+        None => return false,
+    };
+    !matches!(map.get_component(coord), Some(MapComponent::WALL) | None)
+}
+
+/// Move the entity to the tile immediately.
+pub fn teleport(world: &mut World, entity: ObjectId, coord: Vec2) {
+    if let Some(position) = world.positions.get_mut(entity) {
+        position.coord = coord;
+    }
+    if let Some(motion) = world.motions.get_mut(entity) {
+        motion.stop();
+    }
+}
+
+/// Walk toward the direction on the floor map sliding along walls. Return whether the entity moved.
+pub fn walk(world: &mut World, map: Handle<RandomMap>, entity: ObjectId, in_dir: Vec2) -> bool {
+    let coord = match world.positions.get(entity) {
+        Some(position) => position.coord,
+        None => return false,
+    };
+
+    let mut move_dir = Vec2::new(0, 0);
+    let dir = Vec2::new(in_dir.x, 0);
+    if is_walkable(world, map, coord + dir) {
+        move_dir += dir;
+    }
+    let dir = Vec2::new(0, in_dir.y);
+    if is_walkable(world, map, coord + dir) {
+        move_dir += dir;
+    }
+    if move_dir == vector2::ZERO || !is_walkable(world, map, coord + move_dir) {
+        // Blocked actor still turns to the direction
+        if let Some(animator) = world.animators.get_mut(entity) {
+            animator.face(in_dir);
+        }
+        return false;
+    }
+
+    trace!("entity {} moves ({}, {})", entity, move_dir.x, move_dir.y);
+    // Logical move is done at once and animation catches up with it
+    world.positions.get_mut(entity).unwrap().coord += move_dir;
+    if let Some(animator) = world.animators.get_mut(entity) {
+        animator.face(move_dir);
+    }
+    world.events.moved.publish(Moved {
+        entity,
+        from: coord,
+        to: coord + move_dir,
+    });
+    if let Some(motion) = world.motions.get_mut(entity) {
+        motion.start_move(move_dir);
+    }
+    true
+}
+
+/// Let the actor controlled by AI act. Return the cost, or `None` to wait for animation.
+///
+/// Rolls of behaviors are to be drawn from `_rng`, the stream for gameplay.
+pub fn ai_act(world: &mut World, entity: ObjectId, _rng: &mut Random) -> Option<usize> {
+    if world.motions.get(entity).is_some_and(|m| m.is_animating()) {
+        return None;
+    }
+    // `Ai::Wait` is the only behavior, which spends the turn doing nothing
+    Some(ENERGY_TO_ACT)
+}
+
+/// Return pixel position of the entity interpolated between last and current update step.
+pub fn draw_position(
+    world: &World,
+    entity: ObjectId,
+    metrics: &TileMetrics,
+    alpha: f64,
+) -> Option<Vec2> {
+    let pos = metrics.to_pixel(world.positions.get(entity)?.coord);
+    match world.motions.get(entity) {
+        Some(motion) => Some(pos + motion.pixel_offset(metrics.screen_size, alpha)),
+        None => Some(pos),
+    }
+}
+
+/// Return entities in the order of drawing.
+///
+/// Sorted by `RenderOrder`, and then entities on lower tile are drawn in front.
+pub fn render_order(world: &World) -> Vec<ObjectId> {
+    let mut order: Vec<_> = world
+        .render_orders
+        .iter()
+        .map(|(e, o)| {
+            let y = world.positions.get(e).map_or(0, |p| p.coord.y);
+            (o.layer, o.z_order, y, e)
+        })
+        .collect();
+    order.sort();
+    order.into_iter().map(|(_, _, _, e)| e).collect()
+}
+
+/// Draw all visible entities through the camera.
+pub fn render(world: &World, camera: &Camera, metrics: &TileMetrics, alpha: f64) {
+    let size = metrics.screen_size;
+    for entity in render_order(world) {
+        if let Some(map) = world.maps.get(entity) {
+            map.draw(camera, metrics);
+        } else if let (Some(sprite), Some(pos)) = (
+            world.sprites.get(entity),
+            draw_position(world, entity, metrics, alpha),
+        ) {
+            let dist = camera.world_rect_to_screen(Rect::new(pos.x, pos.y, size, size));
+            match world.animators.get(entity) {
+                Some(animator) => draw_image(animator.sheet.image, animator.source_rect(), dist),
+                None => draw_rect(dist, sprite.color),
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wani_character::hero;
+    use crate::wani_character::sprite_sheet::Facing;
+    use crate::wani_core::random::RandomStream;
+    use crate::wani_ecs::component::MOVE_DURATION;
+    use crate::wani_map::random_map;
+    use std::cell::Cell;
+    use std::rc::Rc;
+
+    #[test]
+    fn draw_map_before_actors() {
+        let mut world = World::new();
+        let map = RandomMap::new(20, 20, &mut Random::derive(1, RandomStream::Map));
+
+        // Spawned in reverse of drawing
+        let front = world.spawn(hero::components(Vec2::new(1, 5)));
+        let back = world.spawn(hero::components(Vec2::new(1, 2)));
+        let map = world.spawn(random_map::components(map));
+
+        assert_eq!(render_order(&world), vec![map, back, front]);
+
+        // Z-order goes before the tile row
+        world.render_orders.get_mut(back).unwrap().z_order = 1;
+        assert_eq!(render_order(&world), vec![map, front, back]);
+    }
+
+    #[test]
+    fn walk_along_wall() {
+        let mut world = World::new();
+        let mut rng = Random::derive(1, RandomStream::Map);
+        let map = RandomMap::new(20, 20, &mut rng);
+        let start = map.respawnable_coord(&mut rng);
+        let map = world.spawn(random_map::components(map));
+        let map = world.handle(map).unwrap();
+        let hero = world.spawn(hero::components(start));
+
+        let moves = Rc::new(Cell::new(0));
+        let m = moves.clone();
+        world.events.moved.subscribe(move |_| m.set(m.get() + 1));
+
+        // Outer wall is never passed
+        let mut walked = 0;
+        for _ in 0..30 {
+            if walk(&mut world, map, hero, Vec2::new(-1, -1)) {
+                walked += 1;
+            }
+            let coord = world.positions.get(hero).unwrap().coord;
+            assert!(is_walkable(&world, map, coord));
+        }
+
+        // Every step is reported on delivery
+        assert_eq!(moves.get(), 0);
+        world.events.deliver();
+        assert_eq!(moves.get(), walked);
+    }
+
+    #[test]
+    fn face_sliding_direction() {
+        let mut world = World::new();
+        let mut map = RandomMap::new(20, 20, &mut Random::derive(1, RandomStream::Map));
+
+        // Open floor left behind is not used for collision
+        let mut old = RandomMap::new(20, 20, &mut Random::derive(1, RandomStream::Map));
+        for row in old.map.iter_mut() {
+            row.iter_mut().for_each(|tile| *tile = MapComponent::ROOM);
+        }
+        world.spawn(random_map::components(old));
+
+        // Corridor going up with walls on both sides
+        for (y, row) in map.map.iter_mut().enumerate() {
+            for (x, tile) in row.iter_mut().enumerate() {
+                let room = x == 5 && (2..8).contains(&y);
+                *tile = if room {
+                    MapComponent::ROOM
+                } else {
+                    MapComponent::WALL
+                };
+            }
+        }
+        let map = world.spawn(random_map::components(map));
+        let map = world.handle(map).unwrap();
+        let hero = world.spawn(hero::components(Vec2::new(5, 7)));
+        let facing = |w: &World| w.animators.get(hero).unwrap().facing;
+
+        // Hero looks where it slides, not into the wall
+        assert!(walk(&mut world, map, hero, Vec2::new(-1, -1)));
+        assert!(world.positions.get(hero).unwrap().coord == Vec2::new(5, 6));
+        assert_eq!(facing(&world), Facing::Up);
+
+        // Blocked hero turns to the input
+        teleport(&mut world, hero, Vec2::new(5, 2));
+        assert!(!walk(&mut world, map, hero, Vec2::new(-1, -1)));
+        assert_eq!(facing(&world), Facing::Left);
+    }
+
+    #[test]
+    fn finish_move_in_time() {
+        let mut world = World::new();
+        let hero = world.spawn(hero::components(Vec2::new(3, 3)));
+        let finished = Rc::new(Cell::new(0));
+        let f = finished.clone();
+        world
+            .events
+            .animation_finished
+            .subscribe(move |_| f.set(f.get() + 1));
+
+        world
+            .motions
+            .get_mut(hero)
+            .unwrap()
+            .start_move(Vec2::new(1, 0));
+        let metrics = TileMetrics::default();
+        let size = metrics.screen_size as isize;
+        assert!(
+            draw_position(&world, hero, &metrics, 0.0).unwrap() == Vec2::new(2 * size, 3 * size)
+        );
+
+        // Half of the time is half of the way
+        update_poses(&mut world);
+        animate(&mut world, MOVE_DURATION / 2.0);
+        assert_eq!(world.animators.get(hero).unwrap().pose(), Pose::Walk);
+        let pos = draw_position(&world, hero, &metrics, 1.0).unwrap();
+        assert!(pos == Vec2::new(2 * size + size / 2, 3 * size));
+
+        animate(&mut world, MOVE_DURATION / 2.0);
+        animate(&mut world, MOVE_DURATION / 2.0);
+        update_poses(&mut world);
+        world.events.deliver();
+        assert_eq!(finished.get(), 1);
+        assert!(!world.motions.get(hero).unwrap().is_animating());
+        assert_eq!(world.animators.get(hero).unwrap().pose(), Pose::Idle);
+    }
+}
@@ -0,0 +1,321 @@
+//! Struct of world storing entities and their components.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::debug;
+
+use crate::wani_core::handle::{Handle, IdAllocator, ObjectId};
+use crate::wani_ecs::component::*;
+use crate::wani_ecs::event::{Despawned, EventBus, Spawned};
+use crate::wani_ecs::storage::Storage;
+use crate::wani_map::random_map::RandomMap;
+
+/// Store of entities and their components.
+///
+/// Systems borrow storages of each component separately,
+/// so they can read the map while moving actors without locking.
+///
+/// `spawn_later` and `despawn_later` are queued,
+/// and applied by `apply_commands` between update steps.
+/// Events published by systems are queued in `events` likewise.
+#[derive(Default)]
+pub struct World {
+    entities: IdAllocator,
+
+    pub positions: Storage<Position>,
+    pub motions: Storage<Motion>,
+    pub render_orders: Storage<RenderOrder>,
+    pub sprites: Storage<Sprite>,
+    pub animators: Storage<Animator>,
+    pub ais: Storage<Ai>,
+    pub players: Storage<Player>,
+    pub actors: Storage<Actor>,
+    pub maps: Storage<RandomMap>,
+
+    pub events: EventBus,
+
+    spawn_queue: Vec<(ObjectId, Components)>,
+    despawn_queue: Vec<ObjectId>,
+}
+
+impl World {
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    fn insert(&mut self, entity: ObjectId, c: Components) {
+        self.entities.activate(entity);
+        if let Some(position) = c.position {
+            self.positions.insert(entity, position);
+        }
+        if let Some(motion) = c.motion {
+            self.motions.insert(entity, motion);
+        }
+        if let Some(render_order) = c.render_order {
+            self.render_orders.insert(entity, render_order);
+        }
+        if let Some(sprite) = c.sprite {
+            self.sprites.insert(entity, sprite);
+        }
+        if let Some(animator) = c.animator {
+            self.animators.insert(entity, animator);
+        }
+        if let Some(ai) = c.ai {
+            self.ais.insert(entity, ai);
+        }
+        if let Some(player) = c.player {
+            self.players.insert(entity, player);
+        }
+        if let Some(actor) = c.actor {
+            self.actors.insert(entity, actor);
+        }
+        if let Some(map) = c.map {
+            self.maps.insert(entity, map);
+        }
+    }
+
+    /// Add entity with the components now, and publish `Spawned` of it.
+    pub fn spawn(&mut self, components: Components) -> ObjectId {
+        let entity = self.entities.reserve();
+        self.insert(entity, components);
+        debug!("spawn entity {}", entity);
+        self.events.spawned.publish(Spawned { entity });
+        entity
+    }
+
+    /// Remove entity and its components now, and publish `Despawned` of it.
+    ///
+    /// Return whether it was alive.
+    pub fn despawn(&mut self, entity: ObjectId) -> bool {
+        if !self.remove(entity) {
+            return false;
+        }
+        self.events.despawned.publish(Despawned { entity });
+        true
+    }
+
+    fn remove(&mut self, entity: ObjectId) -> bool {
+        if !self.is_alive(entity) {
+            return false;
+        }
+        self.positions.remove(entity);
+        self.motions.remove(entity);
+        self.render_orders.remove(entity);
+        self.sprites.remove(entity);
+        self.animators.remove(entity);
+        self.ais.remove(entity);
+        self.players.remove(entity);
+        self.actors.remove(entity);
+        self.maps.remove(entity);
+        self.entities.release(entity);
+        debug!("despawn entity {}", entity);
+        true
+    }
+
+    /// Queue entity to be added, and return its id reserved now.
+    pub fn spawn_later(&mut self, components: Components) -> ObjectId {
+        let entity = self.entities.reserve();
+        self.spawn_queue.push((entity, components));
+        entity
+    }
+
+    /// Queue entity to be removed.
+    pub fn despawn_later(&mut self, entity: ObjectId) {
+        self.despawn_queue.push(entity);
+    }
+
+    /// Return whether the entity is queued to be removed.
+    pub fn is_despawning(&self, entity: ObjectId) -> bool {
+        self.despawn_queue.contains(&entity)
+    }
+
+    /// Apply queued spawns and then despawns, and publish `Spawned` and `Despawned` of them.
+    pub fn apply_commands(&mut self) {
+        let spawns: Vec<_> = self.spawn_queue.drain(..).collect();
+        let mut spawned = Vec::with_capacity(spawns.len());
+        for (entity, components) in spawns {
+            self.insert(entity, components);
+            debug!("spawn entity {}", entity);
+            spawned.push(entity);
+        }
+
+        let despawns: Vec<_> = self.despawn_queue.drain(..).collect();
+        let mut despawned = Vec::new();
+        for entity in despawns {
+            if self.remove(entity) {
+                despawned.push(entity);
+            } else {
+                debug!("entity {} is already despawned", entity);
+            }
+        }
+        // Despawned entity is not reported as spawned
+        spawned.retain(|&e| self.is_alive(e));
+
+        for entity in spawned {
+            self.events.spawned.publish(Spawned { entity });
+        }
+        for entity in despawned {
+            self.events.despawned.publish(Despawned { entity });
+        }
+    }
+
+    /// Return handle of the entity if it has component `T`.
+    pub fn handle<T: Component>(&self, entity: ObjectId) -> Option<Handle<T>> {
+        if T::storage(self).contains(entity) {
+            Some(Handle::new(entity))
+        } else {
+            None
+        }
+    }
+
+    /// Return the component of the entity, or `None` if it is removed.
+    pub fn get<T: Component>(&self, handle: Handle<T>) -> Option<&T> {
+        T::storage(self).get(handle.id())
+    }
+
+    pub fn get_mut<T: Component>(&mut self, handle: Handle<T>) -> Option<&mut T> {
+        T::storage_mut(self).get_mut(handle.id())
+    }
+
+    /// Return whether the entity exists. Removed entity returns false.
+    pub fn is_alive(&self, entity: ObjectId) -> bool {
+        self.entities.is_alive(entity)
+    }
+}
+
+/// Component stored in `World`, which can be reached by `Handle`.
+pub trait Component: Sized {
+    fn storage(world: &World) -> &Storage<Self>;
+    fn storage_mut(world: &mut World) -> &mut Storage<Self>;
+}
+
+macro_rules! impl_component {
+    ($type:ty, $storage:ident) => {
+        impl Component for $type {
+            fn storage(world: &World) -> &Storage<Self> {
+                &world.$storage
+            }
+
+            fn storage_mut(world: &mut World) -> &mut Storage<Self> {
+                &mut world.$storage
+            }
+        }
+    };
+}
+
+impl_component!(Position, positions);
+impl_component!(Motion, motions);
+impl_component!(RenderOrder, render_orders);
+impl_component!(Sprite, sprites);
+impl_component!(Animator, animators);
+impl_component!(Ai, ais);
+impl_component!(Player, players);
+impl_component!(Actor, actors);
+impl_component!(RandomMap, maps);
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wani_core::vector2::Vec2;
+
+    fn at(x: isize, y: isize) -> Components {
+        Components {
+            position: Some(Position::new(Vec2::new(x, y))),
+            ..Default::default()
+        }
+    }
+
+    #[test]
+    fn stale_entity() {
+        let mut world = World::new();
+        let old = world.spawn(at(1, 2));
+        assert!(world.despawn(old));
+        assert!(!world.is_alive(old));
+
+        // Slot is reused by next generation
+        let new = world.spawn(at(3, 4));
+        assert_eq!(new.index(), old.index());
+        assert!(!world.is_alive(old));
+        assert!(world.positions.get(old).is_none());
+        assert!(world.positions.get(new).unwrap().coord == Vec2::new(3, 4));
+    }
+
+    #[test]
+    fn apply_commands_later() {
+        let mut world = World::new();
+        let a = world.spawn_later(at(0, 0));
+        assert!(!world.is_alive(a));
+        assert!(world.positions.get(a).is_none());
+
+        // Spawned and despawned at once never appears
+        let b = world.spawn_later(at(0, 0));
+        world.despawn_later(b);
+
+        world.apply_commands();
+        assert!(world.is_alive(a) && !world.is_alive(b));
+
+        // Despawn of `b` is reported though its spawn is not
+        world.events.deliver();
+        let spawned: Vec<_> = world
+            .events
+            .spawned
+            .read()
+            .iter()
+            .map(|e| e.entity)
+            .collect();
+        let despawned: Vec<_> = world
+            .events
+            .despawned
+            .read()
+            .iter()
+            .map(|e| e.entity)
+            .collect();
+        assert_eq!(spawned, vec![a]);
+        assert_eq!(despawned, vec![b]);
+    }
+
+    #[test]
+    fn publish_immediate_changes() {
+        let mut world = World::new();
+        let a = world.spawn(at(0, 0));
+        assert!(world.despawn(a));
+        assert!(!world.despawn(a));
+
+        world.events.deliver();
+        let spawned: Vec<_> = world
+            .events
+            .spawned
+            .read()
+            .iter()
+            .map(|e| e.entity)
+            .collect();
+        let despawned: Vec<_> = world
+            .events
+            .despawned
+            .read()
+            .iter()
+            .map(|e| e.entity)
+            .collect();
+        assert_eq!(spawned, vec![a]);
+        assert_eq!(despawned, vec![a]);
+    }
+
+    #[test]
+    fn typed_handle() {
+        let mut world = World::new();
+        let id = world.spawn(at(1, 2));
+        assert!(world.handle::<RandomMap>(id).is_none());
+        let handle = world.handle::<Position>(id).unwrap();
+        world.get_mut(handle).unwrap().coord = Vec2::new(5, 6);
+        assert!(world.get(handle).unwrap().coord == Vec2::new(5, 6));
+
+        // Stale handle does not reach the entity in the reused slot
+        world.despawn(id);
+        let new = world.spawn(at(3, 4));
+        assert_eq!(new.index(), id.index());
+        assert!(world.get(handle).is_none());
+    }
+}
@@ -5,26 +5,37 @@
 //! This source code is released under the MIT License
 //! http://opensource.org/licenses/mit-license.php
 
-use std::any::Any;
+// Generator keeps its original index-based style.
+#![allow(
+    clippy::needless_range_loop,
+    clippy::len_zero,
+    clippy::match_like_matches_macro,
+    clippy::single_match,
+    clippy::identity_op
+)]
+
+use std::ops::Range;
+
+use crate::{debug, trace};
 
 use crate::entry::*;
-use crate::game_manager::GameManager;
-use crate::wani_core::camera::DRAW_OFFSET;
+use crate::wani_core::camera::Camera;
+use crate::wani_core::image;
+use crate::wani_core::random::Random;
 // use crate::wani_core::color::Color;
 use crate::wani_core::rect::Rect;
+use crate::wani_core::tile_metrics::TileMetrics;
 use crate::wani_core::vector2;
 use crate::wani_core::vector2::Vec2;
+use crate::wani_ecs::component::{Components, RenderLayer, RenderOrder};
 use crate::wani_map::map_component::MapComponent;
-use crate::wani_trait::drawer::Drawer;
-use crate::wani_trait::game_object::GameObject;
-use crate::wani_trait::updater::Updater;
 
 type Map = Vec<Vec<MapComponent>>;
 
 /// Size of Map
 pub struct MapSize {
-    width: usize,
-    height: usize,
+    pub width: usize,
+    pub height: usize,
 }
 
 /// Entity of map
@@ -35,8 +46,8 @@ pub struct RandomMap {
 }
 
 impl RandomMap {
-    /// Generate random map.
-    pub fn new(width: usize, height: usize) -> Self {
+    /// Generate random map from the random number stream for map.
+    pub fn new(width: usize, height: usize, rng: &mut Random) -> Self {
         let (w, h) = RandomMap::correct_size(width, height);
 
         let mut rm = RandomMap {
@@ -48,11 +59,14 @@ impl RandomMap {
             },
         };
 
-        rm.build_maze();
-        rm.build_room();
+        debug!("generate map {}x{}", w, h);
+
+        rm.build_maze(rng);
+        rm.build_room(rng);
         rm.remove_deadend();
 
         rm.build_draw_map();
+        trace!("map generated");
 
         rm
     }
@@ -71,14 +85,14 @@ impl RandomMap {
     }
 
     /// Initialize step 1: Build a maze by stretching the wall.
-    fn build_maze(&mut self) {
+    fn build_maze(&mut self, rng: &mut Random) {
         self.build_outerwall();
 
         let mut posts = self.make_post();
 
         'post: while posts.len() > 0 {
             // Create base point for wall stretching
-            let post_index_start = urandom(0..posts.len());
+            let post_index_start = rng.urange(0..posts.len());
             let post_start = &posts[post_index_start];
 
             match self.get_component(*post_start).unwrap() {
@@ -99,7 +113,7 @@ impl RandomMap {
 
                                 'dir: while direction.len() > 0 {
                                     // Decide direction to stretch the wall
-                                    let rand = urandom(0..direction.len());
+                                    let rand = rng.urange(0..direction.len());
 
                                     let dir = direction[rand];
                                     direction.remove(rand);
@@ -188,17 +202,17 @@ impl RandomMap {
     }
 
     /// Initialize step 2: Build room.
-    fn build_room(&mut self) {
-        let count_room = random(5..10);
+    fn build_room(&mut self, rng: &mut Random) {
+        let count_room = rng.range(5..10);
 
         for _ in 0..count_room {
-            let retry = random(5..7);
+            let retry = rng.range(5..7);
 
             'retry: for _ in 0..retry {
-                let w = random(8..16) as usize / 2 * 2 + 1;
-                let h = random(8..16) as usize / 2 * 2 + 1;
-                let x = random(1..(self.size.width - w - 1) as isize) as usize / 2 * 2 + 1;
-                let y = random(1..(self.size.height - h - 1) as isize) as usize / 2 * 2 + 1;
+                let w = rng.range(8..16) as usize / 2 * 2 + 1;
+                let h = rng.range(8..16) as usize / 2 * 2 + 1;
+                let x = rng.range(1..(self.size.width - w - 1) as isize) as usize / 2 * 2 + 1;
+                let y = rng.range(1..(self.size.height - h - 1) as isize) as usize / 2 * 2 + 1;
 
                 // Check if the room is not already
                 for i in y..y + h {
@@ -213,6 +227,7 @@ impl RandomMap {
                 }
 
                 // Build the room
+                trace!("build room at ({}, {}) {}x{}", x, y, w, h);
                 for i in y..y + h {
                     for j in x..x + w {
                         self.map[i][j] = MapComponent::ROOM;
@@ -553,53 +568,90 @@ impl RandomMap {
         comp
     }
 
-    /// Return random coordinate of room.
-    pub fn respawnable_coord(&self) -> Vec2 {
+    /// Return range of tiles overlapping the rect in world.
+    pub fn visible_range(&self, world: Rect, tile_size: usize) -> (Range<usize>, Range<usize>) {
+        let tile = tile_size.max(1) as isize;
+        let range = |start: isize, end: isize, len: usize| -> Range<usize> {
+            let first = start.div_euclid(tile).max(0).min(len as isize) as usize;
+            let last = (end + tile - 1).div_euclid(tile).max(0).min(len as isize) as usize;
+            first..last.max(first)
+        };
+
+        (
+            range(world.left(), world.right(), self.size.width),
+            range(world.top(), world.bottom(), self.size.height),
+        )
+    }
+
+    /// Draw visible tiles through the camera.
+    pub fn draw(&self, camera: &Camera, metrics: &TileMetrics) {
+        let (xs, ys) = self.visible_range(camera.visible_world_rect(), metrics.screen_size);
+        for y in ys {
+            let row = &self.draw_map[y];
+            for x in xs.clone() {
+                draw_image(
+                    image::MAPCHIP,
+                    metrics.source_rect(row[x]),
+                    camera.tile_to_screen(Vec2::new(x as isize, y as isize), metrics.screen_size),
+                );
+            }
+        }
+    }
+
+    /// Return random tile coordinate of room.
+    pub fn respawnable_coord(&self, rng: &mut Random) -> Vec2 {
         let w = self.size.width;
         let h = self.size.height;
         loop {
-            let rand_pos = Vec2::new(random(0..w as isize), random(0..h as isize));
+            let rand_pos = Vec2::new(rng.range(0..w as isize), rng.range(0..h as isize));
             match self.get_component(rand_pos).unwrap() {
-                MapComponent::ROOM => return rand_pos * 32,
+                MapComponent::ROOM => {
+                    debug!("respawn at ({}, {})", rand_pos.x, rand_pos.y);
+                    return rand_pos;
+                }
                 _ => {}
             }
         }
     }
 }
 
-impl Updater for RandomMap {
-    fn update(&mut self, _gm: &GameManager) {}
+/// Return components of the floor entity of the map.
+pub fn components(map: RandomMap) -> Components {
+    Components {
+        render_order: Some(RenderOrder::new(RenderLayer::Floor)),
+        map: Some(map),
+        ..Default::default()
+    }
 }
 
-impl Drawer for RandomMap {
-    fn draw(&self) {
-        let screen = SCREEN_SIZE.lock().unwrap();
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wani_core::random::RandomStream;
 
-        let os = *DRAW_OFFSET.lock().unwrap();
+    #[test]
+    fn same_seed_same_map() {
+        let a = RandomMap::new(80, 50, &mut Random::derive(42, RandomStream::Map));
+        let b = RandomMap::new(80, 50, &mut Random::derive(42, RandomStream::Map));
+        assert!(a.draw_map == b.draw_map);
+    }
 
-        let mut rect = Rect::new(os.x, os.y, 32, 32);
-        let x_slide = Vec2::new(32, 0);
-        let y_slide = Vec2::new(0, 32);
+    #[test]
+    fn visible_range_is_clipped() {
+        let map = RandomMap::new(80, 50, &mut Random::derive(42, RandomStream::Map));
 
-        for i in &self.draw_map {
-            for j in i {
-                if screen.contains_rect(rect) {
-                    draw_image(
-                        0,
-                        Rect::new((j % 8 * 32) as isize, (j / 8 * 32) as isize, 32, 32),
-                        rect,
-                    );
-                }
-                rect.slide(&x_slide);
-            }
-            rect.x = os.x;
-            rect.slide(&y_slide);
-        }
-    }
-}
+        let (xs, ys) = map.visible_range(Rect::new(0, 0, 640, 480), 32);
+        assert_eq!((xs, ys), (0..20, 0..15));
+
+        // Partially visible tiles are included
+        let (xs, ys) = map.visible_range(Rect::new(48, 16, 640, 480), 32);
+        assert_eq!((xs, ys), (1..22, 0..16));
+
+        // Tiles outside of the map are excluded
+        let (xs, ys) = map.visible_range(Rect::new(2400, -400, 640, 480), 32);
+        assert_eq!((xs, ys), (75..83, 0..3));
 
-impl GameObject for RandomMap {
-    fn as_any(&self) -> &Any {
-        self
+        let (xs, ys) = map.visible_range(Rect::new(4000, 4000, 640, 480), 32);
+        assert!(xs.is_empty() && ys.is_empty());
     }
 }
@@ -0,0 +1,49 @@
+//! Module for platform backend.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use std::cell::RefCell;
+
+use crate::wani_trait::platform::Platform;
+
+pub mod draw_buffer;
+pub mod headless;
+pub mod memory;
+#[cfg(not(target_arch = "wasm32"))]
+pub mod software;
+#[cfg(target_arch = "wasm32")]
+pub mod wasm;
+
+/// Backend used when no other platform is set.
+#[cfg(target_arch = "wasm32")]
+pub type DefaultPlatform = wasm::WasmPlatform;
+#[cfg(not(target_arch = "wasm32"))]
+pub type DefaultPlatform = headless::HeadlessPlatform;
+
+thread_local! {
+    static PLATFORM: RefCell<Box<dyn Platform>> = RefCell::new(Box::new(DefaultPlatform::new()));
+}
+
+/// Replace the platform of current thread and return previous one.
+pub fn set_platform(platform: Box<dyn Platform>) -> Box<dyn Platform> {
+    PLATFORM.with(|p| p.replace(platform))
+}
+
+/// Log message even if the platform is in use, e.g. panic while drawing.
+pub fn report(log: &str) {
+    PLATFORM.with(|p| match p.try_borrow_mut() {
+        Ok(mut p) => p.log(log),
+        Err(_) => DefaultPlatform::new().log(log),
+    })
+}
+
+/// Call function with the platform of current thread.
+pub fn with_platform<F, R>(f: F) -> R
+where
+    F: FnOnce(&mut dyn Platform) -> R,
+{
+    PLATFORM.with(|p| f(&mut **p.borrow_mut()))
+}
@@ -0,0 +1,156 @@
+//! Buffer of draw commands shared with JavaScript.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::wani_core::color::Color;
+use crate::wani_core::rect::Rect;
+use crate::wani_trait::platform::Platform;
+
+/// Command `[RECT, x, y, w, h, rgba]`
+pub const RECT: i32 = 0;
+/// Command `[IMAGE, index, sx, sy, sw, sh, dx, dy, dw, dh]`
+pub const IMAGE: i32 = 1;
+
+/// Draw commands of a frame encoded as `i32` words.
+///
+/// `painter.js` reads it from linear memory and replays it at once.
+#[derive(Default)]
+pub struct DrawBuffer {
+    commands: Vec<i32>,
+}
+
+impl DrawBuffer {
+    pub fn new() -> Self {
+        DrawBuffer {
+            commands: Vec::new(),
+        }
+    }
+
+    pub fn clear(&mut self) {
+        self.commands.clear();
+    }
+
+    pub fn push_rect(&mut self, rect: Rect, color: Color) {
+        let rgba = (color.r as u32) << 24
+            | (color.g as u32) << 16
+            | (color.b as u32) << 8
+            | color.a as u32;
+        self.commands.extend_from_slice(&[
+            RECT,
+            rect.x as i32,
+            rect.y as i32,
+            rect.w as i32,
+            rect.h as i32,
+            rgba as i32,
+        ]);
+    }
+
+    pub fn push_image(&mut self, index: usize, src: Rect, dist: Rect) {
+        self.commands.extend_from_slice(&[
+            IMAGE,
+            index as i32,
+            src.x as i32,
+            src.y as i32,
+            src.w as i32,
+            src.h as i32,
+            dist.x as i32,
+            dist.y as i32,
+            dist.w as i32,
+            dist.h as i32,
+        ]);
+    }
+
+    /// Return encoded commands.
+    pub fn as_slice(&self) -> &[i32] {
+        &self.commands
+    }
+
+    /// Decode commands and draw them to the platform as `painter.js` does.
+    pub fn replay(&self, platform: &mut dyn Platform) {
+        let c = &self.commands;
+        let mut i = 0;
+        while i < c.len() {
+            match c[i] {
+                RECT => {
+                    let rgba = c[i + 5] as u32;
+                    platform.draw_rect(
+                        Rect::new(
+                            c[i + 1] as isize,
+                            c[i + 2] as isize,
+                            c[i + 3] as usize,
+                            c[i + 4] as usize,
+                        ),
+                        Color::new(
+                            (rgba >> 24) as u8,
+                            (rgba >> 16) as u8,
+                            (rgba >> 8) as u8,
+                            rgba as u8,
+                        ),
+                    );
+                    i += 6;
+                }
+                IMAGE => {
+                    platform.draw_image(
+                        c[i + 1] as usize,
+                        Rect::new(
+                            c[i + 2] as isize,
+                            c[i + 3] as isize,
+                            c[i + 4] as usize,
+                            c[i + 5] as usize,
+                        ),
+                        Rect::new(
+                            c[i + 6] as isize,
+                            c[i + 7] as isize,
+                            c[i + 8] as usize,
+                            c[i + 9] as usize,
+                        ),
+                    );
+                    i += 10;
+                }
+                _ => break,
+            }
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::wani_platform::headless::HeadlessPlatform;
+
+    #[test]
+    fn encode_and_replay() {
+        let mut buffer = DrawBuffer::new();
+        buffer.push_rect(Rect::new(-1, 2, 3, 4), Color::new(0x12, 0x34, 0x56, 0x78));
+        buffer.push_image(0, Rect::new(32, 64, 32, 32), Rect::new(-8, 8, 32, 32));
+
+        assert_eq!(
+            buffer.as_slice(),
+            &[
+                RECT,
+                -1,
+                2,
+                3,
+                4,
+                0x1234_5678,
+                IMAGE,
+                0,
+                32,
+                64,
+                32,
+                32,
+                -8,
+                8,
+                32,
+                32
+            ][..]
+        );
+
+        let mut platform = HeadlessPlatform::new();
+        buffer.replay(&mut platform);
+        assert_eq!(platform.draw_count, 2);
+    }
+}
@@ -0,0 +1,74 @@
+//! Platform backend without any display.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use std::time::{Instant, SystemTime, UNIX_EPOCH};
+
+use crate::wani_core::color::Color;
+use crate::wani_core::rect::Rect;
+use crate::wani_trait::platform::Platform;
+
+/// Platform for native target and tests.
+///
+/// Log is written to stdout and draw calls are only counted.
+pub struct HeadlessPlatform {
+    start: Instant,
+    state: u64,
+    pub draw_count: usize,
+}
+
+impl HeadlessPlatform {
+    pub fn new() -> Self {
+        let nanos = SystemTime::now()
+            .duration_since(UNIX_EPOCH)
+            .map(|d| d.as_nanos() as u64)
+            .unwrap_or(0);
+
+        HeadlessPlatform {
+            start: Instant::now(),
+            // xorshift must not start from zero
+            state: nanos | 1,
+            draw_count: 0,
+        }
+    }
+}
+
+impl Default for HeadlessPlatform {
+    fn default() -> Self {
+        Self::new()
+    }
+}
+
+impl Platform for HeadlessPlatform {
+    fn log(&mut self, log: &str) {
+        println!("{}", log);
+    }
+
+    fn random(&mut self, max: isize) -> isize {
+        // xorshift64
+        self.state ^= self.state << 13;
+        self.state ^= self.state >> 7;
+        self.state ^= self.state << 17;
+
+        if max <= 0 {
+            0
+        } else {
+            (self.state % max as u64) as isize
+        }
+    }
+
+    fn draw_rect(&mut self, _rect: Rect, _color: Color) {
+        self.draw_count += 1;
+    }
+
+    fn draw_image(&mut self, _index: usize, _src: Rect, _dist: Rect) {
+        self.draw_count += 1;
+    }
+
+    fn now(&self) -> f64 {
+        self.start.elapsed().as_secs_f64() * 1000.0
+    }
+}
@@ -0,0 +1,74 @@
+//! Memory shared with the host.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+//!
+//! Bytes are passed as pointer and length in both directions.
+//!
+//! - To the host: the host copies bytes during the imported function call.
+//!   The memory is owned by Rust and is not valid after the call.
+//! - From the host: the host gets memory by `alloc`, writes bytes to it
+//!   and passes it to the exported function, which takes the ownership.
+//!   Memory not passed to Rust must be freed by `dealloc`.
+
+use std::alloc::{self, Layout};
+use std::ptr::NonNull;
+use std::slice;
+
+/// Allocate memory of `size` bytes.
+pub fn alloc(size: usize) -> *mut u8 {
+    if size == 0 {
+        return NonNull::dangling().as_ptr();
+    }
+    unsafe { alloc::alloc(Layout::array::<u8>(size).unwrap()) }
+}
+
+/// Free memory allocated by `alloc`.
+///
+/// # Safety
+/// `ptr` and `size` must be a pair returned by and passed to `alloc`.
+pub unsafe fn dealloc(ptr: *mut u8, size: usize) {
+    if size != 0 {
+        alloc::dealloc(ptr, Layout::array::<u8>(size).unwrap());
+    }
+}
+
+/// Take the ownership of bytes written to memory from `alloc`.
+///
+/// # Safety
+/// `ptr` and `len` must be a pair returned by and passed to `alloc`.
+pub unsafe fn take_bytes(ptr: *mut u8, len: usize) -> Vec<u8> {
+    let bytes = slice::from_raw_parts(ptr, len).to_vec();
+    dealloc(ptr, len);
+    bytes
+}
+
+/// Take the ownership of UTF-8 text written to memory from `alloc`.
+///
+/// Invalid sequences are replaced with U+FFFD.
+///
+/// # Safety
+/// `ptr` and `len` must be a pair returned by and passed to `alloc`.
+pub unsafe fn take_string(ptr: *mut u8, len: usize) -> String {
+    String::from_utf8_lossy(&take_bytes(ptr, len)).into_owned()
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn pass_string_from_host() {
+        let text = "ワニ rogue";
+        let ptr = alloc(text.len());
+        unsafe {
+            ptr.copy_from_nonoverlapping(text.as_ptr(), text.len());
+            assert_eq!(take_string(ptr, text.len()), text);
+        }
+
+        let empty = alloc(0);
+        assert!(unsafe { take_bytes(empty, 0) }.is_empty());
+    }
+}
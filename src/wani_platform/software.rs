@@ -0,0 +1,268 @@
+//! Platform backend rasterising draw calls into memory.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use std::cell::RefCell;
+use std::fs::File;
+use std::io;
+use std::io::{BufReader, BufWriter, Write};
+use std::path::Path;
+use std::rc::Rc;
+
+use crate::wani_core::color::Color;
+use crate::wani_core::rect::Rect;
+use crate::wani_platform::headless::HeadlessPlatform;
+use crate::wani_trait::platform::Platform;
+
+/// RGBA image in memory.
+#[derive(Clone, PartialEq)]
+pub struct Canvas {
+    width: usize,
+    height: usize,
+    pixels: Vec<u8>,
+}
+
+impl Canvas {
+    /// Create transparent canvas.
+    pub fn new(width: usize, height: usize) -> Self {
+        Canvas {
+            width,
+            height,
+            pixels: vec![0; width * height * 4],
+        }
+    }
+
+    /// Decode PNG file.
+    pub fn load_png<P: AsRef<Path>>(path: P) -> io::Result<Self> {
+        let mut decoder = png::Decoder::new(BufReader::new(File::open(path)?));
+        decoder.set_transformations(png::Transformations::normalize_to_color8());
+        let mut reader = decoder.read_info().map_err(invalid_data)?;
+        let mut buf = vec![0; reader.output_buffer_size()];
+        let info = reader.next_frame(&mut buf).map_err(invalid_data)?;
+
+        let (width, height) = (info.width as usize, info.height as usize);
+        let pixels = match info.color_type {
+            png::ColorType::Rgba => buf[..width * height * 4].to_vec(),
+            png::ColorType::Rgb => buf[..width * height * 3]
+                .chunks(3)
+                .flat_map(|c| vec![c[0], c[1], c[2], 0xff])
+                .collect(),
+            png::ColorType::GrayscaleAlpha => buf[..width * height * 2]
+                .chunks(2)
+                .flat_map(|c| vec![c[0], c[0], c[0], c[1]])
+                .collect(),
+            png::ColorType::Grayscale => buf[..width * height]
+                .iter()
+                .flat_map(|&c| vec![c, c, c, 0xff])
+                .collect(),
+            png::ColorType::Indexed => {
+                return Err(invalid_data("indexed color is not expanded"));
+            }
+        };
+
+        Ok(Canvas {
+            width,
+            height,
+            pixels,
+        })
+    }
+
+    /// Encode to PNG file.
+    pub fn save_png<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let w = BufWriter::new(File::create(path)?);
+        let mut encoder = png::Encoder::new(w, self.width as u32, self.height as u32);
+        encoder.set_color(png::ColorType::Rgba);
+        encoder.set_depth(png::BitDepth::Eight);
+        let mut writer = encoder.write_header().map_err(invalid_data)?;
+        writer.write_image_data(&self.pixels).map_err(invalid_data)
+    }
+
+    /// Encode to binary PPM file. Alpha channel is dropped.
+    pub fn save_ppm<P: AsRef<Path>>(&self, path: P) -> io::Result<()> {
+        let mut w = BufWriter::new(File::create(path)?);
+        write!(w, "P6\n{} {}\n255\n", self.width, self.height)?;
+        for c in self.pixels.chunks(4) {
+            w.write_all(&c[..3])?;
+        }
+        w.flush()
+    }
+
+    pub fn width(&self) -> usize {
+        self.width
+    }
+
+    pub fn height(&self) -> usize {
+        self.height
+    }
+
+    /// Return RGBA bytes of all pixels.
+    pub fn pixels(&self) -> &[u8] {
+        &self.pixels
+    }
+
+    /// Return RGBA of the pixel.
+    pub fn pixel(&self, x: usize, y: usize) -> [u8; 4] {
+        let i = (y * self.width + x) * 4;
+        [
+            self.pixels[i],
+            self.pixels[i + 1],
+            self.pixels[i + 2],
+            self.pixels[i + 3],
+        ]
+    }
+
+    /// Make all pixels transparent.
+    pub fn clear(&mut self) {
+        for p in self.pixels.iter_mut() {
+            *p = 0;
+        }
+    }
+
+    /// Fill rectangle like `fillRect` of canvas.
+    pub fn fill_rect(&mut self, rect: Rect, color: Color) {
+        let (x0, x1) = clip(rect.left(), rect.right(), self.width);
+        let (y0, y1) = clip(rect.top(), rect.bottom(), self.height);
+
+        for y in y0..y1 {
+            for x in x0..x1 {
+                self.blend(x, y, [color.r, color.g, color.b, color.a]);
+            }
+        }
+    }
+
+    /// Copy scaled part of image like `drawImage` of canvas.
+    pub fn draw_image(&mut self, image: &Canvas, src: Rect, dist: Rect) {
+        if dist.w == 0 || dist.h == 0 {
+            return;
+        }
+        let (x0, x1) = clip(dist.left(), dist.right(), self.width);
+        let (y0, y1) = clip(dist.top(), dist.bottom(), self.height);
+
+        for y in y0..y1 {
+            // Nearest neighbour sampling
+            let sy = src.y + ((y as isize - dist.y) * src.h as isize) / dist.h as isize;
+            if sy < 0 || image.height as isize <= sy {
+                continue;
+            }
+            for x in x0..x1 {
+                let sx = src.x + ((x as isize - dist.x) * src.w as isize) / dist.w as isize;
+                if sx < 0 || image.width as isize <= sx {
+                    continue;
+                }
+                self.blend(x, y, image.pixel(sx as usize, sy as usize));
+            }
+        }
+    }
+
+    /// Composite source over the pixel.
+    fn blend(&mut self, x: usize, y: usize, src: [u8; 4]) {
+        let i = (y * self.width + x) * 4;
+        let sa = src[3] as u32;
+        let da = self.pixels[i + 3] as u32;
+        let oa = sa + da * (255 - sa) / 255;
+        if oa == 0 {
+            return;
+        }
+        for (dst, &src) in self.pixels[i..i + 3].iter_mut().zip(&src[..3]) {
+            let s = src as u32 * sa;
+            let d = *dst as u32 * da * (255 - sa) / 255;
+            *dst = ((s + d) / oa) as u8;
+        }
+        self.pixels[i + 3] = oa as u8;
+    }
+}
+
+/// Clip range to `0..max`.
+fn clip(start: isize, end: isize, max: usize) -> (usize, usize) {
+    let s = start.max(0).min(max as isize) as usize;
+    let e = end.max(0).min(max as isize) as usize;
+    (s, e)
+}
+
+fn invalid_data<E>(e: E) -> io::Error
+where
+    E: Into<Box<dyn std::error::Error + Send + Sync>>,
+{
+    io::Error::new(io::ErrorKind::InvalidData, e)
+}
+
+/// Platform drawing to `Canvas` instead of web browser.
+///
+/// Images are indexed in the order of `load_image` as `resource.js` does.
+pub struct SoftwarePlatform {
+    headless: HeadlessPlatform,
+    canvas: Rc<RefCell<Canvas>>,
+    images: Vec<Canvas>,
+}
+
+impl SoftwarePlatform {
+    pub fn new(width: usize, height: usize) -> Self {
+        SoftwarePlatform {
+            headless: HeadlessPlatform::new(),
+            canvas: Rc::new(RefCell::new(Canvas::new(width, height))),
+            images: Vec::new(),
+        }
+    }
+
+    /// Load image and return its index.
+    pub fn load_image<P: AsRef<Path>>(&mut self, path: P) -> io::Result<usize> {
+        self.images.push(Canvas::load_png(path)?);
+        Ok(self.images.len() - 1)
+    }
+
+    /// Return canvas to be drawn.
+    ///
+    /// It is shared, so it can be read after the platform is set.
+    pub fn canvas(&self) -> Rc<RefCell<Canvas>> {
+        Rc::clone(&self.canvas)
+    }
+}
+
+impl Platform for SoftwarePlatform {
+    fn log(&mut self, log: &str) {
+        self.headless.log(log);
+    }
+
+    fn random(&mut self, max: isize) -> isize {
+        self.headless.random(max)
+    }
+
+    fn begin_frame(&mut self) {
+        self.canvas.borrow_mut().clear();
+    }
+
+    fn draw_rect(&mut self, rect: Rect, color: Color) {
+        self.canvas.borrow_mut().fill_rect(rect, color);
+    }
+
+    fn draw_image(&mut self, index: usize, src: Rect, dist: Rect) {
+        // Not loaded image is skipped as painter.js does
+        if let Some(image) = self.images.get(index) {
+            self.canvas.borrow_mut().draw_image(image, src, dist);
+        }
+    }
+
+    fn now(&self) -> f64 {
+        self.headless.now()
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn blend_rect() {
+        let mut canvas = Canvas::new(4, 4);
+        canvas.fill_rect(Rect::new(-1, -1, 3, 3), Color::new(0xff, 0, 0, 0xff));
+        canvas.fill_rect(Rect::new(1, 1, 8, 8), Color::new(0, 0, 0xff, 0x80));
+
+        assert!(canvas.pixel(0, 0) == [0xff, 0, 0, 0xff]);
+        assert!(canvas.pixel(1, 1) == [0x7f, 0, 0x80, 0xff]);
+        assert!(canvas.pixel(3, 3) == [0, 0, 0xff, 0x80]);
+        assert!(canvas.pixel(2, 0) == [0, 0, 0, 0]);
+    }
+}
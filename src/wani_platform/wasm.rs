@@ -0,0 +1,63 @@
+//! Platform backend of imports from JavaScript.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::wani_core::color::Color;
+use crate::wani_core::rect::Rect;
+use crate::wani_platform::draw_buffer::DrawBuffer;
+use crate::wani_trait::platform::Platform;
+
+extern "C" {
+    fn js_log(ptr: *const u8, len: usize);
+    fn js_random(max: isize) -> isize;
+    fn js_now() -> f64;
+}
+
+/// Platform running in web browser.
+///
+/// Draw calls are batched into `DrawBuffer` and replayed by `painter.js`.
+#[derive(Default)]
+pub struct WasmPlatform {
+    buffer: DrawBuffer,
+}
+
+impl WasmPlatform {
+    pub fn new() -> Self {
+        WasmPlatform {
+            buffer: DrawBuffer::new(),
+        }
+    }
+}
+
+impl Platform for WasmPlatform {
+    fn log(&mut self, log: &str) {
+        unsafe { js_log(log.as_ptr(), log.len()) }
+    }
+
+    fn random(&mut self, max: isize) -> isize {
+        unsafe { js_random(max) }
+    }
+
+    fn begin_frame(&mut self) {
+        self.buffer.clear();
+    }
+
+    fn draw_rect(&mut self, rect: Rect, color: Color) {
+        self.buffer.push_rect(rect, color);
+    }
+
+    fn draw_image(&mut self, index: usize, src: Rect, dist: Rect) {
+        self.buffer.push_image(index, src, dist);
+    }
+
+    fn draw_buffer(&self) -> &[i32] {
+        self.buffer.as_slice()
+    }
+
+    fn now(&self) -> f64 {
+        unsafe { js_now() }
+    }
+}
@@ -0,0 +1,14 @@
+//! Module for game state.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+pub mod game_over;
+pub mod inventory;
+pub mod new_game;
+pub mod pause;
+pub mod playing;
+pub mod state_stack;
+pub mod title;
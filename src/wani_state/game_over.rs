@@ -0,0 +1,46 @@
+//! State of game over.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::wani_core::color::Color;
+use crate::wani_core::input_manager::InputKey;
+use crate::wani_state::new_game::NewGame;
+use crate::wani_state::state_stack::Context;
+use crate::wani_state::title::Title;
+use crate::wani_trait::state::{State, Transition};
+
+/// Show the ended run until the player restarts.
+pub struct GameOver;
+
+impl State for GameOver {
+    fn name(&self) -> &'static str {
+        "game over"
+    }
+
+    fn update(&mut self, _ctx: &mut Context, _elapsed: f64) -> Transition {
+        Transition::None
+    }
+
+    fn draw(&self, ctx: &Context) {
+        // The run stays on screen as it ended
+        if let Some(game) = ctx.game.as_ref() {
+            game.draw();
+        }
+        ctx.fill_screen(Color::new(0x60, 0x00, 0x00, 0xa0));
+    }
+
+    fn key_down(&mut self, ctx: &mut Context, key_code: usize) -> Transition {
+        if InputKey::CONFIRM.matches(key_code) {
+            ctx.end_game();
+            Transition::Replace(Box::new(NewGame))
+        } else if InputKey::CANCEL.matches(key_code) {
+            ctx.end_game();
+            Transition::Replace(Box::new(Title))
+        } else {
+            Transition::None
+        }
+    }
+}
@@ -0,0 +1,69 @@
+//! State of inventory menu.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::entry::draw_rect;
+use crate::wani_core::color::Color;
+use crate::wani_core::input_manager::InputKey;
+use crate::wani_core::rect::Rect;
+use crate::wani_state::state_stack::Context;
+use crate::wani_trait::state::{State, Transition};
+
+/// Size of a slot in pixels.
+const SLOT_SIZE: usize = 32;
+/// Space between slots in pixels.
+const SLOT_MARGIN: usize = 4;
+/// Number of slots in a row.
+const SLOT_COLUMNS: usize = 10;
+/// Number of slots.
+const SLOT_COUNT: usize = 20;
+
+/// Modal menu showing slots of items over the game.
+pub struct InventoryMenu;
+
+impl State for InventoryMenu {
+    fn name(&self) -> &'static str {
+        "inventory"
+    }
+
+    fn update(&mut self, _ctx: &mut Context, _elapsed: f64) -> Transition {
+        Transition::None
+    }
+
+    fn draw(&self, ctx: &Context) {
+        let pitch = SLOT_SIZE + SLOT_MARGIN;
+        let columns = SLOT_COLUMNS;
+        let rows = SLOT_COUNT.div_ceil(columns);
+        let w = columns * pitch + SLOT_MARGIN;
+        let h = rows * pitch + SLOT_MARGIN;
+        let center = ctx.screen().center();
+        let panel = Rect::new(center.x - w as isize / 2, center.y - h as isize / 2, w, h);
+        draw_rect(panel, Color::new(0x20, 0x20, 0x20, 0xe0));
+
+        // Items are not carried yet, so every slot is empty
+        for i in 0..SLOT_COUNT {
+            let slot = Rect::new(
+                panel.left() + (SLOT_MARGIN + i % columns * pitch) as isize,
+                panel.top() + (SLOT_MARGIN + i / columns * pitch) as isize,
+                SLOT_SIZE,
+                SLOT_SIZE,
+            );
+            draw_rect(slot, Color::new(0x50, 0x50, 0x50, 0xff));
+        }
+    }
+
+    fn key_down(&mut self, _ctx: &mut Context, key_code: usize) -> Transition {
+        if InputKey::CANCEL.matches(key_code) || InputKey::INVENTORY.matches(key_code) {
+            Transition::Pop
+        } else {
+            Transition::None
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,30 @@
+//! State of starting a run.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::wani_core::color::Color;
+use crate::wani_state::playing::Playing;
+use crate::wani_state::state_stack::Context;
+use crate::wani_trait::state::{State, Transition};
+
+/// Generate a floor and go to playing.
+pub struct NewGame;
+
+impl State for NewGame {
+    fn name(&self) -> &'static str {
+        "new game"
+    }
+
+    fn update(&mut self, ctx: &mut Context, _elapsed: f64) -> Transition {
+        ctx.start_game();
+        Transition::Replace(Box::new(Playing))
+    }
+
+    fn draw(&self, ctx: &Context) {
+        // Same color as the fade of entering the floor
+        ctx.fill_screen(Color::new(0x00, 0x00, 0x00, 0xff));
+    }
+}
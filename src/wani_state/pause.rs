@@ -0,0 +1,40 @@
+//! State of pausing a run.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::wani_core::color::Color;
+use crate::wani_core::input_manager::InputKey;
+use crate::wani_state::state_stack::Context;
+use crate::wani_trait::state::{State, Transition};
+
+/// Stop the game under this until resumed.
+pub struct Pause;
+
+impl State for Pause {
+    fn name(&self) -> &'static str {
+        "pause"
+    }
+
+    fn update(&mut self, _ctx: &mut Context, _elapsed: f64) -> Transition {
+        Transition::None
+    }
+
+    fn draw(&self, ctx: &Context) {
+        ctx.fill_screen(Color::new(0x00, 0x00, 0x00, 0x80));
+    }
+
+    fn key_down(&mut self, _ctx: &mut Context, key_code: usize) -> Transition {
+        if InputKey::CANCEL.matches(key_code) || InputKey::CONFIRM.matches(key_code) {
+            Transition::Pop
+        } else {
+            Transition::None
+        }
+    }
+
+    fn is_overlay(&self) -> bool {
+        true
+    }
+}
@@ -0,0 +1,57 @@
+//! State of playing a run.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::wani_core::input_manager::InputKey;
+use crate::wani_state::game_over::GameOver;
+use crate::wani_state::inventory::InventoryMenu;
+use crate::wani_state::pause::Pause;
+use crate::wani_state::state_stack::Context;
+use crate::wani_state::title::Title;
+use crate::wani_trait::state::{State, Transition};
+
+/// Run the game of the context.
+pub struct Playing;
+
+impl State for Playing {
+    fn name(&self) -> &'static str {
+        "playing"
+    }
+
+    fn update(&mut self, ctx: &mut Context, elapsed: f64) -> Transition {
+        let game = match ctx.game.as_mut() {
+            Some(game) => game,
+            None => return Transition::Replace(Box::new(Title)),
+        };
+        game.update(elapsed);
+
+        // Killed hero is despawned
+        if !game.world().is_alive(game.hero.id()) {
+            Transition::Replace(Box::new(GameOver))
+        } else {
+            Transition::None
+        }
+    }
+
+    fn draw(&self, ctx: &Context) {
+        if let Some(game) = ctx.game.as_ref() {
+            game.draw();
+        }
+    }
+
+    fn key_down(&mut self, ctx: &mut Context, key_code: usize) -> Transition {
+        if InputKey::CANCEL.matches(key_code) {
+            return Transition::Push(Box::new(Pause));
+        }
+        if InputKey::INVENTORY.matches(key_code) {
+            return Transition::Push(Box::new(InventoryMenu));
+        }
+        if let Some(game) = ctx.game.as_mut() {
+            game.input_mut().key_down(key_code);
+        }
+        Transition::None
+    }
+}
@@ -0,0 +1,276 @@
+//! Struct of stack of game states.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::{debug, info};
+
+use crate::entry::draw_rect;
+use crate::game::Game;
+use crate::wani_core::color::Color;
+use crate::wani_core::rect::Rect;
+use crate::wani_core::tile_metrics::TileMetrics;
+use crate::wani_platform::with_platform;
+use crate::wani_trait::state::{State, Transition};
+
+/// Values shared by all states.
+///
+/// Settings from the platform are kept here, so a run started later gets them.
+pub struct Context {
+    screen: Rect,
+    tile_metrics: TileMetrics,
+    zoom: f64,
+
+    /// Seed of next run. If 0, it is decided by the platform.
+    pub next_seed: u32,
+    /// Current run. `None` before starting and after ending.
+    pub game: Option<Game>,
+}
+
+impl Context {
+    pub fn new(screen: Rect, seed: u32) -> Self {
+        Context {
+            screen,
+            tile_metrics: TileMetrics::default(),
+            zoom: 1.0,
+
+            next_seed: seed,
+            game: None,
+        }
+    }
+
+    pub fn screen(&self) -> Rect {
+        self.screen
+    }
+
+    /// Follow the change of screen size.
+    pub fn resize(&mut self, screen: Rect) {
+        self.screen = screen;
+        if let Some(game) = self.game.as_mut() {
+            game.resize(screen);
+        }
+    }
+
+    /// Change size of tile in tileset image and on screen.
+    pub fn set_tile_metrics(&mut self, tile_metrics: TileMetrics) {
+        self.tile_metrics = tile_metrics;
+        if let Some(game) = self.game.as_mut() {
+            game.set_tile_metrics(tile_metrics);
+        }
+    }
+
+    /// Change magnification of drawing.
+    pub fn set_zoom(&mut self, zoom: f64) {
+        self.zoom = zoom;
+        if let Some(game) = self.game.as_mut() {
+            game.set_zoom(zoom);
+        }
+    }
+
+    /// Start new run with the settings, and drop current one.
+    ///
+    /// The seed is used once, so next run generates another floor.
+    pub fn start_game(&mut self) -> &mut Game {
+        let seed = match self.next_seed {
+            0 => (with_platform(|p| p.random(isize::MAX)) as u32).max(1),
+            seed => seed,
+        };
+        self.next_seed = 0;
+        info!("seed: {}", seed);
+
+        let mut game = Game::new(seed, self.screen);
+        game.set_tile_metrics(self.tile_metrics);
+        game.set_zoom(self.zoom);
+        self.game.insert(game)
+    }
+
+    /// Drop current run.
+    pub fn end_game(&mut self) {
+        self.game = None;
+    }
+
+    /// Cover the screen with the color.
+    pub fn fill_screen(&self, color: Color) {
+        draw_rect(self.screen, color);
+    }
+}
+
+/// Stack of states over shared context.
+///
+/// The top state runs, and states under it wait until it is popped.
+pub struct StateStack {
+    ctx: Context,
+    states: Vec<Box<dyn State>>,
+}
+
+impl StateStack {
+    pub fn new(ctx: Context, first: Box<dyn State>) -> Self {
+        debug!("enter state {}", first.name());
+        StateStack {
+            ctx,
+            states: vec![first],
+        }
+    }
+
+    pub fn context(&self) -> &Context {
+        &self.ctx
+    }
+
+    pub fn context_mut(&mut self) -> &mut Context {
+        &mut self.ctx
+    }
+
+    /// Return name of the top state, or `None` if all states are finished.
+    pub fn current(&self) -> Option<&'static str> {
+        self.states.last().map(|s| s.name())
+    }
+
+    /// Return number of states in the stack.
+    pub fn len(&self) -> usize {
+        self.states.len()
+    }
+
+    pub fn is_empty(&self) -> bool {
+        self.states.is_empty()
+    }
+
+    fn apply(&mut self, transition: Transition) {
+        match transition {
+            Transition::None => {}
+            Transition::Push(state) => {
+                debug!("push state {}", state.name());
+                self.states.push(state);
+            }
+            Transition::Pop => {
+                if let Some(state) = self.states.pop() {
+                    debug!("pop state {}", state.name());
+                }
+            }
+            Transition::Replace(state) => {
+                if let Some(old) = self.states.pop() {
+                    debug!("leave state {}", old.name());
+                }
+                debug!("enter state {}", state.name());
+                self.states.push(state);
+            }
+        }
+    }
+
+    pub fn update(&mut self, elapsed: f64) {
+        let transition = match self.states.last_mut() {
+            Some(state) => state.update(&mut self.ctx, elapsed),
+            None => return,
+        };
+        self.apply(transition);
+    }
+
+    /// Draw the top state over states seen through it.
+    pub fn draw(&self) {
+        let bottom = self
+            .states
+            .iter()
+            .rposition(|s| !s.is_overlay())
+            .unwrap_or(0);
+        for state in &self.states[bottom..] {
+            state.draw(&self.ctx);
+        }
+    }
+
+    pub fn key_down(&mut self, key_code: usize) {
+        let transition = match self.states.last_mut() {
+            Some(state) => state.key_down(&mut self.ctx, key_code),
+            None => return,
+        };
+        self.apply(transition);
+    }
+
+    /// Release the key in the game even if another state is on top.
+    pub fn key_up(&mut self, key_code: usize) {
+        if let Some(game) = self.ctx.game.as_mut() {
+            game.input_mut().key_up(key_code);
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+    use crate::game::TIME_STEP;
+    use crate::wani_core::input_manager::InputKey;
+    use crate::wani_state::title::Title;
+
+    const ENTER: usize = 13;
+    const ESCAPE: usize = 27;
+    const UP: usize = 38;
+    const I: usize = 73;
+
+    fn start() -> StateStack {
+        let mut stack =
+            StateStack::new(Context::new(Rect::new(0, 0, 640, 480), 1), Box::new(Title));
+        stack.key_down(ENTER);
+        stack.update(TIME_STEP);
+        stack
+    }
+
+    #[test]
+    fn title_to_playing() {
+        let mut stack =
+            StateStack::new(Context::new(Rect::new(0, 0, 640, 480), 1), Box::new(Title));
+        stack.update(TIME_STEP);
+        assert_eq!(stack.current(), Some("title"));
+        assert!(stack.context().game.is_none());
+
+        stack.key_down(ENTER);
+        assert_eq!(stack.current(), Some("new game"));
+        stack.update(TIME_STEP);
+        assert_eq!(stack.current(), Some("playing"));
+        assert_eq!(stack.context().game.as_ref().unwrap().seed(), 1);
+    }
+
+    #[test]
+    fn pause_and_resume() {
+        let mut stack = start();
+        stack.key_down(UP);
+        stack.key_down(ESCAPE);
+        assert_eq!(stack.current(), Some("pause"));
+        assert_eq!(stack.len(), 2);
+
+        // Key released while paused does not keep the hero walking
+        stack.key_up(UP);
+        let game = stack.context().game.as_ref().unwrap();
+        assert!(!game.input().get_key(InputKey::UP));
+
+        // Game does not advance under the pause
+        let turn = game.turn();
+        stack.update(TIME_STEP * 10.0);
+        assert_eq!(stack.context().game.as_ref().unwrap().turn(), turn);
+
+        stack.key_down(ESCAPE);
+        assert_eq!(stack.current(), Some("playing"));
+
+        stack.key_down(I);
+        assert_eq!(stack.current(), Some("inventory"));
+        stack.key_down(I);
+        assert_eq!(stack.current(), Some("playing"));
+    }
+
+    #[test]
+    fn restart_after_game_over() {
+        let mut stack = start();
+        let game = stack.context_mut().game.as_mut().unwrap();
+        let hero = game.hero.id();
+        game.despawn(hero);
+
+        stack.update(TIME_STEP);
+        assert_eq!(stack.current(), Some("game over"));
+        assert_eq!(stack.len(), 1);
+
+        // Next run is not the same floor
+        stack.key_down(ENTER);
+        stack.update(TIME_STEP);
+        assert_eq!(stack.current(), Some("playing"));
+        assert_ne!(stack.context().game.as_ref().unwrap().seed(), 1);
+    }
+}
@@ -0,0 +1,44 @@
+//! State of title screen.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::entry::draw_rect;
+use crate::wani_core::color::Color;
+use crate::wani_core::input_manager::InputKey;
+use crate::wani_core::rect::Rect;
+use crate::wani_state::new_game::NewGame;
+use crate::wani_state::state_stack::Context;
+use crate::wani_trait::state::{State, Transition};
+
+/// First screen waiting for the player to start.
+pub struct Title;
+
+impl State for Title {
+    fn name(&self) -> &'static str {
+        "title"
+    }
+
+    fn update(&mut self, _ctx: &mut Context, _elapsed: f64) -> Transition {
+        Transition::None
+    }
+
+    fn draw(&self, ctx: &Context) {
+        ctx.fill_screen(Color::new(0x10, 0x18, 0x30, 0xff));
+        let center = ctx.screen().center();
+        draw_rect(
+            Rect::new(center.x - 96, center.y - 32, 192, 64),
+            Color::new(0x40, 0x80, 0x40, 0xff),
+        );
+    }
+
+    fn key_down(&mut self, _ctx: &mut Context, key_code: usize) -> Transition {
+        if InputKey::CONFIRM.matches(key_code) {
+            Transition::Replace(Box::new(NewGame))
+        } else {
+            Transition::None
+        }
+    }
+}
@@ -5,6 +5,5 @@
 //! This source code is released under the MIT License
 //! http://opensource.org/licenses/mit-license.php
 
-pub mod drawer;
-pub mod game_object;
-pub mod updater;
+pub mod platform;
+pub mod state;
@@ -0,0 +1,33 @@
+//! Trait of host platform
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::wani_core::color::Color;
+use crate::wani_core::rect::Rect;
+
+/// Services the game needs from the environment it runs in.
+pub trait Platform {
+    /// Output a line of log.
+    fn log(&mut self, log: &str);
+
+    /// Return random number in `0..max`.
+    fn random(&mut self, max: isize) -> isize;
+
+    /// Prepare for drawing new frame.
+    fn begin_frame(&mut self) {}
+
+    fn draw_rect(&mut self, rect: Rect, color: Color);
+
+    fn draw_image(&mut self, index: usize, src: Rect, dist: Rect);
+
+    /// Return draw commands of current frame if the platform batches them.
+    fn draw_buffer(&self) -> &[i32] {
+        &[]
+    }
+
+    /// Return elapsed time in milliseconds.
+    fn now(&self) -> f64;
+}
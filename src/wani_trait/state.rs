@@ -0,0 +1,43 @@
+//! Trait of game state
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use crate::wani_state::state_stack::Context;
+
+/// Change of the state stack requested by the top state.
+pub enum Transition {
+    /// Stay in current state.
+    None,
+    /// Suspend current state and run new one over it.
+    Push(Box<dyn State>),
+    /// Finish current state and resume the one under it.
+    Pop,
+    /// Finish current state and run new one instead.
+    Replace(Box<dyn State>),
+}
+
+/// Scene of the game such as title screen or menu.
+///
+/// Only the top state of the stack is updated and receives pressed keys.
+pub trait State {
+    /// Name for log.
+    fn name(&self) -> &'static str;
+
+    /// Advance the state by elapsed milliseconds.
+    fn update(&mut self, ctx: &mut Context, elapsed: f64) -> Transition;
+
+    fn draw(&self, ctx: &Context);
+
+    /// Handle pressed key. Released keys always reach input of the game.
+    fn key_down(&mut self, _ctx: &mut Context, _key_code: usize) -> Transition {
+        Transition::None
+    }
+
+    /// Return whether the state under this one is drawn behind it.
+    fn is_overlay(&self) -> bool {
+        false
+    }
+}
@@ -0,0 +1,77 @@
+//! Golden image test of rendering.
+//!
+//! Run with `UPDATE_GOLDEN=1` to regenerate the images in `tests/golden`.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use std::env;
+use std::fs;
+use std::path::PathBuf;
+
+use wani_rogue::wani_character::hero;
+use wani_rogue::wani_core::camera::Camera;
+use wani_rogue::wani_core::image;
+use wani_rogue::wani_core::random::{Random, RandomStream};
+use wani_rogue::wani_core::rect::Rect;
+use wani_rogue::wani_core::tile_metrics::TileMetrics;
+use wani_rogue::wani_core::vector2::Vec2;
+use wani_rogue::wani_ecs::system;
+use wani_rogue::wani_ecs::world::World;
+use wani_rogue::wani_map::random_map;
+use wani_rogue::wani_map::random_map::RandomMap;
+use wani_rogue::wani_platform::set_platform;
+use wani_rogue::wani_platform::software::{Canvas, SoftwarePlatform};
+
+fn path(name: &str) -> PathBuf {
+    PathBuf::from(env!("CARGO_MANIFEST_DIR")).join(name)
+}
+
+fn assert_golden(canvas: &Canvas, name: &str) {
+    let golden = path(&format!("tests/golden/{}.png", name));
+    if env::var_os("UPDATE_GOLDEN").is_some() {
+        canvas.save_png(&golden).unwrap();
+        return;
+    }
+
+    let expected = Canvas::load_png(&golden).unwrap();
+    if expected != *canvas {
+        let dir = path("target/golden");
+        fs::create_dir_all(&dir).unwrap();
+        let actual = dir.join(format!("{}.png", name));
+        canvas.save_png(&actual).unwrap();
+        panic!("{} differs from golden image, see {:?}", name, actual);
+    }
+}
+
+#[test]
+fn seeded_floor() {
+    let mut rng = Random::derive(2019, RandomStream::Map);
+    let map = RandomMap::new(40, 30, &mut rng);
+    let coord = map.respawnable_coord(&mut rng);
+
+    let metrics = TileMetrics::default();
+    let tile = metrics.screen_size;
+    let (w, h) = (map.size.width * tile, map.size.height * tile);
+
+    let mut world = World::new();
+    world.spawn(random_map::components(map));
+    world.spawn(hero::components(coord));
+
+    let mut camera = Camera::new();
+    camera.set_screen(Rect::new(0, 0, w, h));
+    camera.look_at(Vec2::new(w as isize / 2, h as isize / 2));
+
+    let mut platform = SoftwarePlatform::new(w, h);
+    for image in image::PATHS.iter() {
+        platform.load_image(path("app").join(image)).unwrap();
+    }
+    let canvas = platform.canvas();
+    set_platform(Box::new(platform));
+
+    system::render(&world, &camera, &metrics, 0.0);
+
+    assert_golden(&canvas.borrow(), "seeded_floor");
+}
@@ -0,0 +1,34 @@
+//! Test of halting by panic.
+//!
+//! Copyright (c) 2019 wanigame
+//!
+//! This source code is released under the MIT License
+//! http://opensource.org/licenses/mit-license.php
+
+use std::panic;
+
+use wani_rogue::entry;
+
+#[test]
+fn halt_after_panic() {
+    entry::init(640, 480, 1);
+    entry::update(20.0);
+    entry::draw();
+    assert!(!entry::is_halted());
+
+    let result = panic::catch_unwind(|| panic!("test panic"));
+    assert!(result.is_err());
+    assert!(entry::is_halted());
+
+    // Halted game does nothing instead of panicking again
+    entry::key_down(38);
+    entry::key_up(38);
+    entry::resize(320, 240);
+    entry::set_tile_metrics(16, 16, 8);
+    entry::set_zoom(2.0);
+    assert_eq!(entry::get_seed(), 0);
+    entry::update(20.0);
+    entry::draw();
+    assert!(entry::draw_buffer_ptr().is_null());
+    assert_eq!(entry::draw_buffer_len(), 0);
+}